@@ -0,0 +1,100 @@
+//! Compares the batch API with applying the scalar functions one dual-quaternion at a time
+//!
+//! Run with `cargo bench --features alloc`, adding `simd,std` for the SSE and AVX paths.
+
+#[macro_use]
+extern crate criterion;
+extern crate dual_quaternion;
+extern crate quaternion;
+
+use criterion::{black_box, Criterion};
+use dual_quaternion::{DualQuaternion, DualQuaternionBatch};
+
+const N: usize = 4096;
+
+fn poses(seed: f32) -> Vec<DualQuaternion<f32>> {
+    (0..N).map(|i| {
+        let a = i as f32 * 0.01 + seed;
+        let axis = [a.cos(), a.sin(), 0.0];
+        dual_quaternion::from_rotation_and_translation(quaternion::axis_angle(axis, a), [a, -a, 1.0])
+    }).collect()
+}
+
+fn bench_mul(c: &mut Criterion) {
+    let (a, b) = (poses(0.0), poses(1.0));
+    let (batch_a, batch_b) = (DualQuaternionBatch::from_slice(&a), DualQuaternionBatch::from_slice(&b));
+    let mut group = c.benchmark_group("mul");
+    let mut dst = vec![dual_quaternion::id(); N];
+    group.bench_function("scalar", |bench| bench.iter(|| {
+        for ((a, b), d) in a.iter().zip(&b).zip(dst.iter_mut()) {
+            *d = dual_quaternion::mul(*a, *b);
+        }
+        black_box(&dst);
+    }));
+    let mut dst = DualQuaternionBatch::identity(N);
+    group.bench_function("batch", |bench| bench.iter(|| {
+        batch_a.mul(&batch_b, &mut dst);
+        black_box(&dst);
+    }));
+    group.finish();
+}
+
+fn bench_normalize(c: &mut Criterion) {
+    let a: Vec<_> = poses(0.0).into_iter().map(|q| dual_quaternion::scale(q, 2.0)).collect();
+    let mut batch = DualQuaternionBatch::from_slice(&a);
+    let mut a = a;
+    let mut group = c.benchmark_group("normalize");
+    group.bench_function("scalar", |bench| bench.iter(|| {
+        for q in a.iter_mut() {
+            *q = dual_quaternion::normalize(*q);
+        }
+        black_box(&a);
+    }));
+    group.bench_function("batch", |bench| bench.iter(|| {
+        batch.normalize();
+        black_box(&batch);
+    }));
+    group.finish();
+}
+
+fn bench_transform_points(c: &mut Criterion) {
+    let a = poses(0.0);
+    let batch = DualQuaternionBatch::from_slice(&a);
+    let src: Vec<_> = (0..N).map(|i| [i as f32, 1.0, -2.0]).collect();
+    let mut dst = vec![[0.0; 3]; N];
+    let mut group = c.benchmark_group("transform_points");
+    group.bench_function("scalar", |bench| bench.iter(|| {
+        for ((q, p), d) in a.iter().zip(&src).zip(dst.iter_mut()) {
+            *d = dual_quaternion::transform_point(*q, *p);
+        }
+        black_box(&dst);
+    }));
+    group.bench_function("batch", |bench| bench.iter(|| {
+        batch.transform_points(&src, &mut dst);
+        black_box(&dst);
+    }));
+    group.finish();
+}
+
+fn bench_blend(c: &mut Criterion) {
+    let (a, b) = (poses(0.0), poses(1.0));
+    let (batch_a, batch_b) = (DualQuaternionBatch::from_slice(&a), DualQuaternionBatch::from_slice(&b));
+    let (wa, wb) = (vec![0.75; N], vec![0.25; N]);
+    let mut group = c.benchmark_group("blend");
+    let mut dst = vec![dual_quaternion::id(); N];
+    group.bench_function("scalar", |bench| bench.iter(|| {
+        for ((a, b), d) in a.iter().zip(&b).zip(dst.iter_mut()) {
+            *d = dual_quaternion::blend(&[(*a, 0.75), (*b, 0.25)]);
+        }
+        black_box(&dst);
+    }));
+    let mut dst = DualQuaternionBatch::identity(N);
+    group.bench_function("batch", |bench| bench.iter(|| {
+        DualQuaternionBatch::blend(&[(&batch_a, &wa[..]), (&batch_b, &wb[..])], &mut dst);
+        black_box(&dst);
+    }));
+    group.finish();
+}
+
+criterion_group!(benches, bench_mul, bench_normalize, bench_transform_points, bench_blend);
+criterion_main!(benches);
@@ -0,0 +1,315 @@
+//! Keyframe animation of joint transforms
+
+use alloc::vec::Vec;
+
+use vecmath::traits::Float;
+
+use skeleton::Skeleton;
+use super::{dot, id, scale, sclerp, DualQuaternion};
+
+/// How a track computes transforms between its keys
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Holds each key until the next one
+    Step,
+    /// Screw linear interpolation between neighbouring keys, see `sclerp`
+    Linear,
+    /// Catmull-Rom interpolation through the keys, built from nested `sclerp`
+    ///
+    /// The first and last keys are repeated to complete the segments at the ends.
+    Cubic,
+}
+
+/// How times outside the range of the keys are mapped into it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WrapMode {
+    /// Holds the first key before the range and the last key after it
+    Clamp,
+    /// Repeats the range, so the last key should match the first one for seamless playback
+    Loop,
+}
+
+/// Unit dual-quaternion keys at increasing times
+///
+/// Each key is aligned to the hemisphere of the previous one when added,
+/// so that interpolation never takes the long way around.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Track<T> {
+    times: Vec<T>,
+    keys: Vec<DualQuaternion<T>>,
+    interpolation: Interpolation,
+}
+
+impl<T: Float> Track<T> {
+    /// Creates a track from key times and transforms, with linear interpolation
+    ///
+    /// Panics if the lengths differ or the times are not strictly increasing.
+    pub fn new(times: Vec<T>, keys: Vec<DualQuaternion<T>>) -> Track<T> {
+        assert_eq!(times.len(), keys.len());
+        let mut track = Track {
+            times: Vec::with_capacity(times.len()),
+            keys: Vec::with_capacity(keys.len()),
+            interpolation: Interpolation::Linear,
+        };
+        for (t, q) in times.into_iter().zip(keys) {
+            track.push(t, q);
+        }
+        track
+    }
+
+    /// Appends a key after the existing ones
+    ///
+    /// Panics if `time` is not greater than the time of the last key.
+    pub fn push(&mut self, time: T, key: DualQuaternion<T>) {
+        let key = match self.keys.last() {
+            Some(&prev) => {
+                assert!(time > self.times[self.times.len() - 1], "key times must be strictly increasing");
+                if dot(prev, key) < T::zero() { scale(key, -T::one()) } else { key }
+            }
+            None => key,
+        };
+        self.times.push(time);
+        self.keys.push(key);
+    }
+
+    /// Returns the key times
+    #[inline(always)]
+    pub fn times(&self) -> &[T] {
+        &self.times
+    }
+
+    /// Returns the key transforms, after hemisphere alignment
+    #[inline(always)]
+    pub fn keys(&self) -> &[DualQuaternion<T>] {
+        &self.keys
+    }
+
+    /// Returns the number of keys
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns `true` if the track has no keys
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns the interpolation mode
+    #[inline(always)]
+    pub fn interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    /// Sets the interpolation mode
+    #[inline(always)]
+    pub fn set_interpolation(&mut self, interpolation: Interpolation) {
+        self.interpolation = interpolation;
+    }
+
+    /// Returns the transform at `time`, mapping times outside the keys with `wrap`
+    ///
+    /// An empty track yields the identity.
+    pub fn sample(&self, time: T, wrap: WrapMode) -> DualQuaternion<T> {
+        let n = self.keys.len();
+        if n == 0 {
+            return id();
+        }
+        let (start, end) = (self.times[0], self.times[n - 1]);
+        let time = wrap_time(time, start, end, wrap);
+        // index of the first key after `time`, so the segment starts at i - 1
+        let i = self.times.partition_point(|&t| t <= time);
+        if i == 0 {
+            return self.keys[0];
+        }
+        if i == n {
+            return self.keys[n - 1];
+        }
+        let (t1, t2) = (self.times[i - 1], self.times[i]);
+        let (q1, q2) = (self.keys[i - 1], self.keys[i]);
+        match self.interpolation {
+            Interpolation::Step => q1,
+            Interpolation::Linear => sclerp(q1, q2, (time - t1) / (t2 - t1)),
+            Interpolation::Cubic => {
+                // repeated end keys get times mirrored across the ends
+                let (t0, q0) = if i >= 2 { (self.times[i - 2], self.keys[i - 2]) } else { (t1 + t1 - t2, q1) };
+                let (t3, q3) = if i + 1 < n { (self.times[i + 1], self.keys[i + 1]) } else { (t2 + t2 - t1, q2) };
+                barry_goldman([q0, q1, q2, q3], [t0, t1, t2, t3], time)
+            }
+        }
+    }
+}
+
+/// Evaluates a Catmull-Rom spline through four keys at times `k` at `t` between the middle two,
+/// with the Barry-Goldman pyramid of `sclerp`
+///
+/// Unlike `spline::catmull_rom`, which assumes uniformly spaced keys,
+/// this parameterizes the curve by the key times.
+fn barry_goldman<T: Float>(q: [DualQuaternion<T>; 4], k: [T; 4], t: T) -> DualQuaternion<T> {
+    let a1 = sclerp(q[0], q[1], (t - k[0]) / (k[1] - k[0]));
+    let a2 = sclerp(q[1], q[2], (t - k[1]) / (k[2] - k[1]));
+    let a3 = sclerp(q[2], q[3], (t - k[2]) / (k[3] - k[2]));
+    let b1 = sclerp(a1, a2, (t - k[0]) / (k[2] - k[0]));
+    let b2 = sclerp(a2, a3, (t - k[1]) / (k[3] - k[1]));
+    sclerp(b1, b2, (t - k[1]) / (k[2] - k[1]))
+}
+
+/// Maps `time` into `[start, end]`
+fn wrap_time<T: Float>(time: T, start: T, end: T, wrap: WrapMode) -> T {
+    match wrap {
+        WrapMode::Clamp => time.max(start).min(end),
+        WrapMode::Loop => {
+            let duration = end - start;
+            if duration <= T::zero() {
+                return start;
+            }
+            let offset = (time - start) % duration;
+            start + if offset < T::zero() { offset + duration } else { offset }
+        }
+    }
+}
+
+/// An animation with one track per joint
+#[derive(Clone, Debug, PartialEq)]
+pub struct Clip<T> {
+    tracks: Vec<Track<T>>,
+    duration: T,
+}
+
+impl<T: Float> Clip<T> {
+    /// Creates a clip from one track per joint
+    ///
+    /// The clip starts at time zero and lasts until the last key of any track.
+    pub fn new(tracks: Vec<Track<T>>) -> Clip<T> {
+        let duration = tracks.iter()
+            .filter_map(|track| track.times.last().cloned())
+            .fold(T::zero(), |a, b| a.max(b));
+        Clip { tracks, duration }
+    }
+
+    /// Returns the tracks
+    #[inline(always)]
+    pub fn tracks(&self) -> &[Track<T>] {
+        &self.tracks
+    }
+
+    /// Returns the time of the last key of any track
+    #[inline(always)]
+    pub fn duration(&self) -> T {
+        self.duration
+    }
+
+    /// Samples every track at `time`, writing the transforms to `dst`
+    ///
+    /// `wrap` maps `time` into the duration of the clip. Tracks are clamped within it,
+    /// and empty tracks yield the identity.
+    ///
+    /// Panics if `dst` doesn't have one transform per track.
+    pub fn sample(&self, time: T, wrap: WrapMode, dst: &mut [DualQuaternion<T>]) {
+        assert_eq!(dst.len(), self.tracks.len());
+        let time = wrap_time(time, T::zero(), self.duration, wrap);
+        for (track, q) in self.tracks.iter().zip(dst.iter_mut()) {
+            *q = track.sample(time, WrapMode::Clamp);
+        }
+    }
+
+    /// Samples the clip at `time` and sets the local transforms of the joints with non-empty tracks
+    ///
+    /// Panics if the skeleton doesn't have one joint per track.
+    pub fn apply(&self, time: T, wrap: WrapMode, skeleton: &mut Skeleton<T>) {
+        assert_eq!(skeleton.len(), self.tracks.len());
+        let time = wrap_time(time, T::zero(), self.duration, wrap);
+        for (j, track) in self.tracks.iter().enumerate() {
+            if !track.is_empty() {
+                skeleton.set_local(j, track.sample(time, WrapMode::Clamp));
+            }
+        }
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod test {
+
+    use std::f64::consts::PI;
+    use quaternion;
+
+    use skeleton::Skeleton;
+    use test_util::assert_close;
+    use super::{Clip, Interpolation, Track, WrapMode};
+    use super::super::{dot, from_rotation_and_translation, id, pow, scale, sclerp, DualQuaternion};
+
+    const EPSILON: f64 = 0.000000001;
+
+    fn turn(angle: f64, x: f64) -> DualQuaternion<f64> {
+        from_rotation_and_translation(quaternion::axis_angle([0.0, 0.0, 1.0], angle), [x, 0.0, 0.0])
+    }
+
+    #[test]
+    fn test_sample_modes() {
+        let keys = vec![turn(0.0, 0.0), turn(PI / 2.0, 1.0), turn(PI, 2.0)];
+        let mut track = Track::new(vec![0.0, 1.0, 3.0], keys.clone());
+
+        assert_close(track.sample(0.5, WrapMode::Clamp), sclerp(keys[0], keys[1], 0.5), EPSILON);
+        assert_close(track.sample(2.0, WrapMode::Clamp), sclerp(keys[1], keys[2], 0.5), EPSILON);
+        assert_close(track.sample(-1.0, WrapMode::Clamp), keys[0], EPSILON);
+        assert_close(track.sample(5.0, WrapMode::Clamp), keys[2], EPSILON);
+        assert_close(track.sample(3.5, WrapMode::Loop), sclerp(keys[0], keys[1], 0.5), EPSILON);
+        assert_close(track.sample(-2.5, WrapMode::Loop), sclerp(keys[0], keys[1], 0.5), EPSILON);
+
+        track.set_interpolation(Interpolation::Step);
+        assert_close(track.sample(0.9, WrapMode::Clamp), keys[0], EPSILON);
+        assert_close(track.sample(1.0, WrapMode::Clamp), keys[1], EPSILON);
+
+        // keys on a constant screw motion are reproduced exactly between the inner keys
+        let m = turn(0.4, 0.3);
+        let mut track = Track::new(vec![0.0, 1.0, 2.0, 3.0], (0..4).map(|k| pow(m, k as f64)).collect());
+        track.set_interpolation(Interpolation::Cubic);
+        for &t in &[1.0, 1.25, 1.5, 1.9, 2.0] {
+            assert_close(track.sample(t, WrapMode::Clamp), pow(m, t), EPSILON);
+        }
+        assert_close(track.sample(0.0, WrapMode::Clamp), id(), EPSILON);
+        assert_close(track.sample(3.0, WrapMode::Clamp), pow(m, 3.0), EPSILON);
+
+        assert_eq!(Track::new(vec![], vec![]).sample(1.0, WrapMode::Loop), id());
+    }
+
+    #[test]
+    fn test_hemisphere_alignment() {
+        let keys = vec![turn(0.0, 0.0), scale(turn(0.5, 1.0), -1.0), turn(1.0, 2.0)];
+        let track = Track::new(vec![0.0, 1.0, 2.0], keys);
+        for pair in track.keys().windows(2) {
+            assert!(dot(pair[0], pair[1]) > 0.0);
+        }
+        assert_close(track.sample(0.5, WrapMode::Clamp), sclerp(turn(0.0, 0.0), turn(0.5, 1.0), 0.5), EPSILON);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_unsorted_times() {
+        Track::new(vec![0.0, 0.0], vec![id::<f64>(); 2]);
+    }
+
+    #[test]
+    fn test_clip() {
+        let clip = Clip::new(vec![
+            Track::new(vec![0.0, 2.0], vec![turn(0.0, 0.0), turn(1.0, 2.0)]),
+            Track::new(vec![], vec![]),
+        ]);
+        assert_eq!(clip.duration(), 2.0);
+
+        let mut dst = [id(); 2];
+        clip.sample(3.0, WrapMode::Loop, &mut dst);
+        let halfway = sclerp(turn(0.0, 0.0), turn(1.0, 2.0), 0.5);
+        assert_close(dst[0], halfway, EPSILON);
+        assert_eq!(dst[1], id());
+
+        let mut skeleton = Skeleton::new(vec![None, Some(0)], vec![id(), turn(0.0, 5.0)]);
+        clip.apply(1.0, WrapMode::Clamp, &mut skeleton);
+        skeleton.update();
+        assert_close(skeleton.locals()[0], halfway, EPSILON);
+        assert_eq!(skeleton.locals()[1], turn(0.0, 5.0));
+    }
+
+}
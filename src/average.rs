@@ -0,0 +1,209 @@
+//! Weighted means of many rigid transforms, such as repeated estimates of the same pose
+//!
+//! `dlb_mean` is a single normalized weighted sum, fast and close to the true mean
+//! when the poses agree. `karcher_mean` iterates on the manifold of unit dual-quaternions
+//! with `log` and `exp` until the mean stops moving.
+
+use vecmath::{self, Vector3};
+use vecmath::traits::Float;
+
+use math;
+use super::{between, blend_iter, dot, exp, get_translation, log, mul, scale, DualQuaternion};
+
+/// A mean pose with the spread of the poses around it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Average<T> {
+    /// The mean pose
+    pub pose: DualQuaternion<T>,
+    /// Weighted root mean square of the rotation angles from the mean to each pose, in radians
+    pub rotation_spread: T,
+    /// Weighted root mean square of the translation lengths from the mean to each pose
+    pub translation_spread: T,
+    /// Number of iterations performed, zero for `dlb_mean`
+    pub iterations: usize,
+    /// Whether the last update of the mean was within the tolerance,
+    /// always `true` for `dlb_mean`, which computes its mean in one step
+    pub converged: bool,
+}
+
+/// Returns the weight of pose `i`, one without weights
+#[inline(always)]
+fn weight<T: Float>(weights: Option<&[T]>, i: usize) -> T {
+    weights.map_or(T::one(), |w| w[i])
+}
+
+/// Returns the sum of the weights
+///
+/// Panics if the poses are empty, the lengths differ or the weights don't have a positive sum.
+fn total_weight<T: Float>(poses: &[DualQuaternion<T>], weights: Option<&[T]>) -> T {
+    assert!(!poses.is_empty());
+    let total = match weights {
+        Some(w) => {
+            assert_eq!(w.len(), poses.len());
+            w.iter().fold(T::zero(), |a, &b| a + b)
+        }
+        None => T::from_f64(poses.len() as f64),
+    };
+    assert!(total > T::zero(), "weights must have a positive sum");
+    total
+}
+
+/// Returns the transform from `mean` to `q`, taking the shortest path
+#[inline(always)]
+fn residual<T: Float>(mean: DualQuaternion<T>, q: DualQuaternion<T>) -> DualQuaternion<T> {
+    let q = if dot(mean, q) < T::zero() { scale(q, -T::one()) } else { q };
+    between(mean, q)
+}
+
+/// Computes the spread of the poses around `pose`
+fn average<T: Float>(
+    pose: DualQuaternion<T>,
+    poses: &[DualQuaternion<T>],
+    weights: Option<&[T]>,
+    total: T,
+    iterations: usize,
+    converged: bool
+) -> Average<T> {
+    let (mut rotation, mut translation) = (T::zero(), T::zero());
+    for (i, &q) in poses.iter().enumerate() {
+        let r = residual(pose, q);
+        let w = weight(weights, i);
+        rotation += w * vecmath::vec3_square_len(log(r).0);
+        translation += w * vecmath::vec3_square_len(get_translation(r));
+    }
+    Average {
+        pose,
+        rotation_spread: math::sqrt(rotation / total),
+        translation_spread: math::sqrt(translation / total),
+        iterations,
+        converged,
+    }
+}
+
+/// Approximates the weighted mean of unit dual-quaternions with dual-quaternion linear blending
+///
+/// Without weights every pose counts the same. See `blend` for how the poses are combined,
+/// the first pose being the blending pivot. A weight may be zero to ignore a pose,
+/// such as `Some(&[0.0, 2.0])` for two poses, as long as the weights have a positive sum.
+///
+/// Panics if `poses` is empty, the weights don't have one entry per pose
+/// or don't have a positive sum, which includes all weights being zero.
+pub fn dlb_mean<T: Float>(poses: &[DualQuaternion<T>], weights: Option<&[T]>) -> Average<T> {
+    let total = total_weight(poses, weights);
+    let pose = blend_iter(poses.iter().enumerate().map(|(i, &q)| (q, weight(weights, i))));
+    average(pose, poses, weights, total, 0, true)
+}
+
+/// Computes the weighted Karcher mean of unit dual-quaternions, the pose from which
+/// the weighted twists towards all poses sum to zero
+///
+/// Starts from `dlb_mean` and moves along the average twist until the move is
+/// shorter than `tolerance`, measuring rotation in radians and translation in length units,
+/// or `max_iterations` is reached.
+///
+/// Panics like `dlb_mean`.
+pub fn karcher_mean<T: Float>(
+    poses: &[DualQuaternion<T>],
+    weights: Option<&[T]>,
+    tolerance: T,
+    max_iterations: usize
+) -> Average<T> {
+    let total = total_weight(poses, weights);
+    let mut mean = blend_iter(poses.iter().enumerate().map(|(i, &q)| (q, weight(weights, i))));
+    let zero: Vector3<T> = [T::zero(); 3];
+    let mut iterations = 0;
+    let mut converged = false;
+    while iterations < max_iterations {
+        let (mut a, mut b) = (zero, zero);
+        for (i, &q) in poses.iter().enumerate() {
+            let twist = log(residual(mean, q));
+            let w = weight(weights, i) / total;
+            a = vecmath::vec3_add(a, vecmath::vec3_scale(twist.0, w));
+            b = vecmath::vec3_add(b, vecmath::vec3_scale(twist.1, w));
+        }
+        mean = mul(mean, exp((a, b)));
+        iterations += 1;
+        if vecmath::vec3_square_len(a) + vecmath::vec3_square_len(b) <= tolerance * tolerance {
+            converged = true;
+            break;
+        }
+    }
+    average(mean, poses, weights, total, iterations, converged)
+}
+
+/// Tests
+#[cfg(test)]
+mod test {
+
+    use quaternion;
+    use vecmath;
+
+    use test_util::assert_close;
+    use super::{dlb_mean, karcher_mean};
+    use super::super::{exp, from_rotation_and_translation, mul, scale, sclerp, DualQuaternion};
+
+    const EPSILON: f64 = 0.000000001;
+
+    fn pose() -> DualQuaternion<f64> {
+        from_rotation_and_translation(quaternion::axis_angle(vecmath::vec3_normalized([1.0, 2.0, -0.5]), 1.2), [3.0, -1.0, 2.0])
+    }
+
+    #[test]
+    fn test_identical_poses() {
+        let poses = [pose(), scale(pose(), -1.0), pose()];
+        for mean in &[dlb_mean(&poses, None), karcher_mean(&poses, None, 1e-12, 10)] {
+            assert_close(mean.pose, pose(), EPSILON);
+            assert!(mean.rotation_spread < 1e-6 && mean.translation_spread < EPSILON);
+            assert!(mean.converged);
+        }
+    }
+
+    #[test]
+    fn test_symmetric_noise() {
+        // each pair of readings deviates from the pose by opposite twists
+        let twists = [
+            ([0.1, 0.0, 0.0], [0.0, 0.02, 0.0]),
+            ([0.0, 0.1, 0.0], [0.0, 0.0, 0.02]),
+            ([0.0, 0.0, 0.1], [0.02, 0.0, 0.0]),
+        ];
+        let mut poses = [pose(); 6];
+        for (i, &(a, b)) in twists.iter().enumerate() {
+            poses[2 * i] = mul(pose(), exp((a, b)));
+            poses[2 * i + 1] = mul(pose(), exp((vecmath::vec3_scale(a, -1.0), vecmath::vec3_scale(b, -1.0))));
+        }
+        let mean = karcher_mean(&poses, None, 1e-12, 20);
+        assert!(mean.converged && mean.iterations < 20);
+        assert_close(mean.pose, pose(), EPSILON);
+        assert!((mean.rotation_spread - 0.1).abs() < EPSILON);
+
+        let approx = dlb_mean(&poses, None);
+        assert_close(approx.pose, pose(), 1e-3);
+        assert_eq!(approx.iterations, 0);
+    }
+
+    #[test]
+    fn test_weights() {
+        let a = pose();
+        let b = mul(pose(), from_rotation_and_translation(quaternion::axis_angle([0.0, 0.0, 1.0], 2.0), [1.0, 0.0, 0.0]));
+        let mean = karcher_mean(&[a, b], Some(&[1.0, 1.0]), 1e-12, 50);
+        assert_close(mean.pose, sclerp(a, b, 0.5), 1e-9);
+        assert!((mean.rotation_spread - 1.0).abs() < 1e-9);
+
+        let mean = karcher_mean(&[a, b], Some(&[0.0, 2.0]), 1e-12, 50);
+        assert_close(mean.pose, b, 1e-9);
+        let mean = dlb_mean(&[a, b], Some(&[0.0, 2.0]));
+        assert_close(mean.pose, b, 1e-9);
+        assert!(mean.converged);
+
+        let capped = karcher_mean(&[a, b, pose()], None, 0.0, 1);
+        assert_eq!(capped.iterations, 1);
+        assert!(!capped.converged);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_weights() {
+        dlb_mean(&[pose(), pose()], Some(&[0.0, 0.0]));
+    }
+
+}
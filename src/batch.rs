@@ -0,0 +1,687 @@
+//! Batches of dual-quaternions in structure-of-arrays layout
+//!
+//! Each component is stored in its own array, so that batched operations
+//! process many dual-quaternions with the same instructions. The operations
+//! apply the scalar functions lane by lane and give bit-identical results to them.
+//!
+//! With the `simd` feature, `f32` batches use SSE on x86_64, or AVX when the
+//! processor supports it and the `std` feature is enabled for runtime detection.
+
+use alloc::vec::Vec;
+use core::iter::FromIterator;
+use core::ops::Deref;
+
+use vecmath::Vector3;
+use vecmath::traits::Float;
+
+use math;
+use super::{add, dot, id, mul, normalize, normalize_blended, scale, transform_point, DualQuaternion};
+
+/// A batch of dual-quaternions with each component stored in its own array
+///
+/// The lanes are, in order, the `w`, `x`, `y` and `z` components of the real part
+/// followed by those of the dual part.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct DualQuaternionBatch<T> {
+    lanes: [Vec<T>; 8],
+}
+
+/// Number of elements processed together
+///
+/// Each chunk is copied to local arrays, so the compiler can vectorize
+/// without having to prove that the lanes don't alias.
+const CHUNK: usize = 8;
+
+type Chunk<T> = [[T; CHUNK]; 8];
+
+/// Reads element `i` from lanes of the same length
+#[inline(always)]
+fn read<T: Copy, S: Deref<Target = [T]>>(l: &[S; 8], i: usize) -> DualQuaternion<T> {
+    ((l[0][i], [l[1][i], l[2][i], l[3][i]]), (l[4][i], [l[5][i], l[6][i], l[7][i]]))
+}
+
+/// Writes element `i` to lanes of the same length
+#[inline(always)]
+fn write<T: Copy>(l: &mut [&mut [T]; 8], i: usize, q: DualQuaternion<T>) {
+    let ((a, [b, c, d]), (e, [f, g, h])) = q;
+    l[0][i] = a;
+    l[1][i] = b;
+    l[2][i] = c;
+    l[3][i] = d;
+    l[4][i] = e;
+    l[5][i] = f;
+    l[6][i] = g;
+    l[7][i] = h;
+}
+
+/// Copies `CHUNK` elements starting at `i` to local arrays
+#[inline(always)]
+fn load_chunk<T: Float, S: Deref<Target = [T]>>(l: &[S; 8], i: usize) -> Chunk<T> {
+    let mut c = [[T::zero(); CHUNK]; 8];
+    for (c, l) in c.iter_mut().zip(l) {
+        c.copy_from_slice(&l[i..i + CHUNK]);
+    }
+    c
+}
+
+/// Copies local arrays back to `CHUNK` elements starting at `i`
+#[inline(always)]
+fn store_chunk<T: Float>(l: &mut [&mut [T]; 8], i: usize, c: &Chunk<T>) {
+    for (l, c) in l.iter_mut().zip(c) {
+        l[i..i + CHUNK].copy_from_slice(c);
+    }
+}
+
+/// Applies `normalize_blended` to every element of a chunk, one operation at a time
+///
+/// This repeats the arithmetic of `normalize` in the same order, so the results are bit-identical,
+/// but lets the compiler vectorize across the elements.
+#[inline(always)]
+fn normalize_blended_chunk<T: Float>(c: &mut Chunk<T>) {
+    let (zero, one) = (T::zero(), T::one());
+    let mut l = chunk_lanes_mut(c);
+    for e in 0..CHUNK {
+        let ((w, [x, y, z]), (dw, [dx, dy, dz])) = read(&l, e);
+        let len2 = w * w + (x * x + y * y + z * z);
+        // computed for every element and replaced by the identity where the real part is zero
+        let r = one / math::sqrt(len2);
+        let (w, x, y, z) = (w * r, x * r, y * r, z * r);
+        let (dw, dx, dy, dz) = (dw * r, dx * r, dy * r, dz * r);
+        let k = -(w * dw + (x * dx + y * dy + z * dz));
+        let select = |a, b| if len2 != zero { a } else { b };
+        write(&mut l, e, (
+            (select(w, one), [select(x, zero), select(y, zero), select(z, zero)]),
+            (select(dw + w * k, zero), [select(dx + x * k, zero), select(dy + y * k, zero), select(dz + z * k, zero)])
+        ));
+    }
+}
+
+/// Returns the lanes of a chunk as slices
+#[inline(always)]
+fn chunk_lanes<T>(c: &Chunk<T>) -> [&[T]; 8] {
+    [&c[0], &c[1], &c[2], &c[3], &c[4], &c[5], &c[6], &c[7]]
+}
+
+/// Returns the lanes of a chunk as mutable slices
+#[inline(always)]
+fn chunk_lanes_mut<T>(c: &mut Chunk<T>) -> [&mut [T]; 8] {
+    let [a, b, x, y, e, f, g, h] = c;
+    [a, b, x, y, e, f, g, h]
+}
+
+impl<T: Float> DualQuaternionBatch<T> {
+    /// Creates an empty batch
+    pub fn new() -> DualQuaternionBatch<T> {
+        DualQuaternionBatch::with_capacity(0)
+    }
+
+    /// Creates an empty batch with room for `n` dual-quaternions
+    pub fn with_capacity(n: usize) -> DualQuaternionBatch<T> {
+        DualQuaternionBatch {
+            lanes: [
+                Vec::with_capacity(n), Vec::with_capacity(n), Vec::with_capacity(n), Vec::with_capacity(n),
+                Vec::with_capacity(n), Vec::with_capacity(n), Vec::with_capacity(n), Vec::with_capacity(n)
+            ],
+        }
+    }
+
+    /// Creates a batch of `n` identity dual-quaternions
+    pub fn identity(n: usize) -> DualQuaternionBatch<T> {
+        let mut batch = DualQuaternionBatch::with_capacity(n);
+        batch.resize(n);
+        batch
+    }
+
+    /// Creates a batch from a slice of dual-quaternions
+    pub fn from_slice(qs: &[DualQuaternion<T>]) -> DualQuaternionBatch<T> {
+        qs.iter().cloned().collect()
+    }
+
+    /// Returns the number of dual-quaternions
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.lanes[0].len()
+    }
+
+    /// Returns `true` if the batch holds no dual-quaternions
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.lanes[0].is_empty()
+    }
+
+    /// Returns the values of one component for every dual-quaternion, see `DualQuaternionBatch`
+    ///
+    /// Panics if `lane` is not less than 8.
+    #[inline(always)]
+    pub fn lane(&self, lane: usize) -> &[T] {
+        &self.lanes[lane]
+    }
+
+    /// Returns the dual-quaternion at index `i`
+    ///
+    /// Panics if `i` is out of bounds.
+    #[inline(always)]
+    pub fn get(&self, i: usize) -> DualQuaternion<T> {
+        read(&self.slices(), i)
+    }
+
+    /// Replaces the dual-quaternion at index `i`
+    ///
+    /// Panics if `i` is out of bounds.
+    #[inline(always)]
+    pub fn set(&mut self, i: usize, q: DualQuaternion<T>) {
+        write(&mut self.slices_mut(), i, q)
+    }
+
+    /// Appends a dual-quaternion
+    pub fn push(&mut self, q: DualQuaternion<T>) {
+        let ((a, [b, c, d]), (e, [f, g, h])) = q;
+        for (lane, x) in self.lanes.iter_mut().zip(&[a, b, c, d, e, f, g, h]) {
+            lane.push(*x);
+        }
+    }
+
+    /// Removes all dual-quaternions
+    pub fn clear(&mut self) {
+        for lane in &mut self.lanes {
+            lane.clear();
+        }
+    }
+
+    /// Resizes the batch to `n` dual-quaternions, filling new entries with the identity
+    pub fn resize(&mut self, n: usize) {
+        let ((a, [b, c, d]), (e, [f, g, h])) = id::<T>();
+        for (lane, x) in self.lanes.iter_mut().zip(&[a, b, c, d, e, f, g, h]) {
+            lane.resize(n, *x);
+        }
+    }
+
+    /// Returns an iterator over the dual-quaternions
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { batch: self, i: 0 }
+    }
+
+    /// Multiplies element-wise, `self[i] * other[i]`, writing the products to `dst`, see `mul`
+    ///
+    /// `dst` is resized to the length of the batches.
+    /// Panics if the batches have different lengths.
+    pub fn mul(&self, other: &DualQuaternionBatch<T>, dst: &mut DualQuaternionBatch<T>) {
+        assert_eq!(self.len(), other.len());
+        dst.resize(self.len());
+        #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+        {
+            if simd::is_f32::<T>() {
+                let (a, b, d) = unsafe { (simd::batch(self), simd::batch(other), simd::batch_mut(dst)) };
+                simd::mul(&a.slices(), &b.slices(), &mut d.slices_mut());
+                return;
+            }
+        }
+        let (a, b) = (self.slices(), other.slices());
+        let mut d = dst.slices_mut();
+        let n = a[0].len();
+        let mut i = 0;
+        while i + CHUNK <= n {
+            let (ca, cb) = (load_chunk(&a, i), load_chunk(&b, i));
+            let (ca, cb) = (chunk_lanes(&ca), chunk_lanes(&cb));
+            let mut cd = [[T::zero(); CHUNK]; 8];
+            {
+                let mut cd = chunk_lanes_mut(&mut cd);
+                for e in 0..CHUNK {
+                    write(&mut cd, e, mul(read(&ca, e), read(&cb, e)));
+                }
+            }
+            store_chunk(&mut d, i, &cd);
+            i += CHUNK;
+        }
+        for i in i..n {
+            write(&mut d, i, mul(read(&a, i), read(&b, i)));
+        }
+    }
+
+    /// Normalizes every dual-quaternion in place, see `normalize`
+    pub fn normalize(&mut self) {
+        let mut l = self.slices_mut();
+        let n = l[0].len();
+        let mut i = 0;
+        while i + CHUNK <= n {
+            let mut c = load_chunk(&l, i);
+            {
+                let mut c = chunk_lanes_mut(&mut c);
+                for e in 0..CHUNK {
+                    let q = read(&c, e);
+                    write(&mut c, e, normalize(q));
+                }
+            }
+            store_chunk(&mut l, i, &c);
+            i += CHUNK;
+        }
+        for i in i..n {
+            let q = read(&l, i);
+            write(&mut l, i, normalize(q));
+        }
+    }
+
+    /// Transforms point `src[i]` by the unit dual-quaternion at index `i`,
+    /// writing the result to `dst[i]`, see `transform_point`
+    ///
+    /// Panics if `src`, `dst` and the batch have different lengths.
+    pub fn transform_points(&self, src: &[Vector3<T>], dst: &mut [Vector3<T>]) {
+        assert_eq!(src.len(), self.len());
+        assert_eq!(dst.len(), self.len());
+        #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+        {
+            if simd::is_f32::<T>() {
+                let (q, src, dst) = unsafe { (simd::batch(self), simd::points(src), simd::points_mut(dst)) };
+                simd::transform_points(&q.slices(), src, dst);
+                return;
+            }
+        }
+        let q = self.slices();
+        let n = src.len();
+        let mut i = 0;
+        while i + CHUNK <= n {
+            let c = load_chunk(&q, i);
+            let c = chunk_lanes(&c);
+            for (e, (p, d)) in src[i..i + CHUNK].iter().zip(&mut dst[i..i + CHUNK]).enumerate() {
+                *d = transform_point(read(&c, e), *p);
+            }
+            i += CHUNK;
+        }
+        for i in i..n {
+            dst[i] = transform_point(read(&q, i), src[i]);
+        }
+    }
+
+    /// Blends batches element-wise with dual-quaternion linear blending,
+    /// writing the results to `dst`, see `blend`
+    ///
+    /// Element `i` of the result blends element `i` of every input batch with the
+    /// weight at index `i` of its slice. The first input is used as the blending pivot.
+    /// `dst` is resized to the length of the batches, or cleared if there are no inputs.
+    ///
+    /// Panics if the batches and weight slices have different lengths.
+    pub fn blend(inputs: &[(&DualQuaternionBatch<T>, &[T])], dst: &mut DualQuaternionBatch<T>) {
+        let n = match inputs.first() {
+            Some(&(pivot, _)) => pivot.len(),
+            None => return dst.clear(),
+        };
+        for &(batch, w) in inputs {
+            assert_eq!(batch.len(), n);
+            assert_eq!(w.len(), n);
+        }
+        dst.resize(n);
+        let zero = T::zero();
+        let (pivot, pw) = (inputs[0].0.slices(), inputs[0].1);
+        let mut d = dst.slices_mut();
+        let mut i = 0;
+        while i + CHUNK <= n {
+            // the arithmetic of `blend`, one operation at a time over the whole chunk
+            let p = load_chunk(&pivot, i);
+            let w = &pw[i..i + CHUNK];
+            let mut sum = [[zero; CHUNK]; 8];
+            for (sum, p) in sum.iter_mut().zip(&p) {
+                for e in 0..CHUNK {
+                    sum[e] = p[e] * w[e];
+                }
+            }
+            for &(batch, w) in &inputs[1..] {
+                let q = batch.slices();
+                let (q, w) = (load_chunk(&q, i), &w[i..i + CHUNK]);
+                let mut signed = [zero; CHUNK];
+                for (e, signed) in signed.iter_mut().enumerate() {
+                    let dot = p[0][e] * q[0][e] + (p[1][e] * q[1][e] + p[2][e] * q[2][e] + p[3][e] * q[3][e]);
+                    *signed = if dot < zero { -w[e] } else { w[e] };
+                }
+                for (sum, q) in sum.iter_mut().zip(&q) {
+                    for e in 0..CHUNK {
+                        sum[e] += q[e] * signed[e];
+                    }
+                }
+            }
+            normalize_blended_chunk(&mut sum);
+            store_chunk(&mut d, i, &sum);
+            i += CHUNK;
+        }
+        for i in i..n {
+            let p = read(&pivot, i);
+            let mut sum = scale(p, pw[i]);
+            for &(batch, w) in &inputs[1..] {
+                let q = batch.get(i);
+                let w = if dot(p, q) < zero { -w[i] } else { w[i] };
+                sum = add(sum, scale(q, w));
+            }
+            write(&mut d, i, normalize_blended(sum));
+        }
+    }
+
+    /// Returns the lanes sliced to the batch length, which lets the compiler drop bounds checks
+    #[inline(always)]
+    fn slices(&self) -> [&[T]; 8] {
+        let n = self.len();
+        let l = &self.lanes;
+        [&l[0][..n], &l[1][..n], &l[2][..n], &l[3][..n], &l[4][..n], &l[5][..n], &l[6][..n], &l[7][..n]]
+    }
+
+    /// Returns the mutable lanes sliced to the batch length
+    #[inline(always)]
+    fn slices_mut(&mut self) -> [&mut [T]; 8] {
+        let n = self.len();
+        let [a, b, c, d, e, f, g, h] = &mut self.lanes;
+        [
+            &mut a[..n], &mut b[..n], &mut c[..n], &mut d[..n],
+            &mut e[..n], &mut f[..n], &mut g[..n], &mut h[..n]
+        ]
+    }
+}
+
+impl<T: Float> FromIterator<DualQuaternion<T>> for DualQuaternionBatch<T> {
+    fn from_iter<I: IntoIterator<Item = DualQuaternion<T>>>(iter: I) -> DualQuaternionBatch<T> {
+        let iter = iter.into_iter();
+        let mut batch = DualQuaternionBatch::with_capacity(iter.size_hint().0);
+        for q in iter {
+            batch.push(q);
+        }
+        batch
+    }
+}
+
+/// An iterator over the dual-quaternions of a batch
+#[derive(Clone, Debug)]
+pub struct Iter<'a, T: 'a> {
+    batch: &'a DualQuaternionBatch<T>,
+    i: usize,
+}
+
+impl<'a, T: Float> Iterator for Iter<'a, T> {
+    type Item = DualQuaternion<T>;
+
+    fn next(&mut self) -> Option<DualQuaternion<T>> {
+        if self.i < self.batch.len() {
+            self.i += 1;
+            Some(self.batch.get(self.i - 1))
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.batch.len() - self.i;
+        (n, Some(n))
+    }
+}
+
+/// SSE and AVX implementations for `f32`, evaluating in the same order as the scalar functions
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod simd {
+    use core::any::TypeId;
+
+    use vecmath::Vector3;
+    use vecmath::traits::Float;
+
+    use super::{read, write, DualQuaternionBatch};
+    use super::super::{mul as scalar_mul, transform_point};
+
+    /// Returns `true` if `T` is `f32`, which makes the casts below valid
+    #[inline(always)]
+    pub fn is_f32<T: Float>() -> bool {
+        TypeId::of::<T>() == TypeId::of::<f32>()
+    }
+
+    #[inline(always)]
+    pub unsafe fn batch<T>(q: &DualQuaternionBatch<T>) -> &DualQuaternionBatch<f32> {
+        &*(q as *const DualQuaternionBatch<T> as *const DualQuaternionBatch<f32>)
+    }
+
+    #[inline(always)]
+    pub unsafe fn batch_mut<T>(q: &mut DualQuaternionBatch<T>) -> &mut DualQuaternionBatch<f32> {
+        &mut *(q as *mut DualQuaternionBatch<T> as *mut DualQuaternionBatch<f32>)
+    }
+
+    #[inline(always)]
+    pub unsafe fn points<T>(p: &[Vector3<T>]) -> &[Vector3<f32>] {
+        &*(p as *const [Vector3<T>] as *const [Vector3<f32>])
+    }
+
+    #[inline(always)]
+    pub unsafe fn points_mut<T>(p: &mut [Vector3<T>]) -> &mut [Vector3<f32>] {
+        &mut *(p as *mut [Vector3<T>] as *mut [Vector3<f32>])
+    }
+
+    /// Returns `true` if AVX can be used
+    #[inline(always)]
+    fn has_avx() -> bool {
+        #[cfg(feature = "std")]
+        {
+            is_x86_feature_detected!("avx")
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            cfg!(target_feature = "avx")
+        }
+    }
+
+    /// Generates the kernels for one vector width
+    macro_rules! kernels {
+        (
+            $name:ident, $width:expr, $feature:expr, $v:ident,
+            $load:ident, $store:ident, $set1:ident, $setzero:ident, $add:ident, $sub:ident, $mul:ident
+        ) => {
+            mod $name {
+                use core::arch::x86_64::*;
+
+                use vecmath::Vector3;
+
+                const W: usize = $width;
+
+                /// `W` lanes of a quaternion
+                #[derive(Clone, Copy)]
+                struct Quat {
+                    w: $v,
+                    v: [$v; 3],
+                }
+
+                #[inline(always)]
+                unsafe fn load(l: &[&[f32]], i: usize) -> Quat {
+                    Quat {
+                        w: $load(l[0][i..i + W].as_ptr()),
+                        v: [$load(l[1][i..i + W].as_ptr()), $load(l[2][i..i + W].as_ptr()), $load(l[3][i..i + W].as_ptr())],
+                    }
+                }
+
+                #[inline(always)]
+                unsafe fn store(l: &mut [&mut [f32]], i: usize, q: Quat) {
+                    $store(l[0][i..i + W].as_mut_ptr(), q.w);
+                    $store(l[1][i..i + W].as_mut_ptr(), q.v[0]);
+                    $store(l[2][i..i + W].as_mut_ptr(), q.v[1]);
+                    $store(l[3][i..i + W].as_mut_ptr(), q.v[2]);
+                }
+
+                #[inline(always)]
+                unsafe fn vec3_add(a: [$v; 3], b: [$v; 3]) -> [$v; 3] {
+                    [$add(a[0], b[0]), $add(a[1], b[1]), $add(a[2], b[2])]
+                }
+
+                #[inline(always)]
+                unsafe fn vec3_scale(a: [$v; 3], s: $v) -> [$v; 3] {
+                    [$mul(a[0], s), $mul(a[1], s), $mul(a[2], s)]
+                }
+
+                #[inline(always)]
+                unsafe fn vec3_dot(a: [$v; 3], b: [$v; 3]) -> $v {
+                    $add($add($mul(a[0], b[0]), $mul(a[1], b[1])), $mul(a[2], b[2]))
+                }
+
+                #[inline(always)]
+                unsafe fn vec3_cross(a: [$v; 3], b: [$v; 3]) -> [$v; 3] {
+                    [
+                        $sub($mul(a[1], b[2]), $mul(a[2], b[1])),
+                        $sub($mul(a[2], b[0]), $mul(a[0], b[2])),
+                        $sub($mul(a[0], b[1]), $mul(a[1], b[0]))
+                    ]
+                }
+
+                /// Quaternion product, see `quaternion::mul`
+                #[inline(always)]
+                unsafe fn quat_mul(a: Quat, b: Quat) -> Quat {
+                    Quat {
+                        w: $sub($mul(a.w, b.w), vec3_dot(a.v, b.v)),
+                        v: vec3_add(vec3_add(vec3_scale(b.v, a.w), vec3_scale(a.v, b.w)), vec3_cross(a.v, b.v)),
+                    }
+                }
+
+                /// Multiplies whole groups of `W` elements and returns how many were done
+                #[target_feature(enable = $feature)]
+                pub unsafe fn mul(a: &[&[f32]; 8], b: &[&[f32]; 8], d: &mut [&mut [f32]; 8]) -> usize {
+                    let n = a[0].len();
+                    let mut i = 0;
+                    while i + W <= n {
+                        let (ar, ad) = (load(&a[..4], i), load(&a[4..], i));
+                        let (br, bd) = (load(&b[..4], i), load(&b[4..], i));
+                        let (x, y) = (quat_mul(ar, bd), quat_mul(ad, br));
+                        store(&mut d[..4], i, quat_mul(ar, br));
+                        store(&mut d[4..], i, Quat { w: $add(x.w, y.w), v: vec3_add(x.v, y.v) });
+                        i += W;
+                    }
+                    i
+                }
+
+                /// Transforms whole groups of `W` points and returns how many were done
+                #[target_feature(enable = $feature)]
+                pub unsafe fn transform_points(q: &[&[f32]; 8], src: &[Vector3<f32>], dst: &mut [Vector3<f32>]) -> usize {
+                    let n = src.len();
+                    let mut i = 0;
+                    while i + W <= n {
+                        let (r, d) = (load(&q[..4], i), load(&q[4..], i));
+
+                        // transpose the points into lanes
+                        let mut lanes = [[0.0f32; W]; 3];
+                        for (j, p) in src[i..i + W].iter().enumerate() {
+                            lanes[0][j] = p[0];
+                            lanes[1][j] = p[1];
+                            lanes[2][j] = p[2];
+                        }
+                        let p = [$load(lanes[0].as_ptr()), $load(lanes[1].as_ptr()), $load(lanes[2].as_ptr())];
+
+                        // rotation, see `quaternion::rotate_vector`
+                        let two = $add($set1(1.0), $set1(1.0));
+                        let t = vec3_scale(vec3_cross(r.v, p), two);
+                        let rotated = vec3_add(vec3_add(p, vec3_scale(t, r.w)), vec3_cross(r.v, t));
+
+                        // translation, see `get_translation`
+                        let two = $set1(2.0);
+                        let d2 = Quat { w: $mul(d.w, two), v: vec3_scale(d.v, two) };
+                        let zero = $setzero();
+                        let conj = Quat { w: r.w, v: [$sub(zero, r.v[0]), $sub(zero, r.v[1]), $sub(zero, r.v[2])] };
+                        let translation = quat_mul(d2, conj).v;
+
+                        let out = vec3_add(rotated, translation);
+                        $store(lanes[0].as_mut_ptr(), out[0]);
+                        $store(lanes[1].as_mut_ptr(), out[1]);
+                        $store(lanes[2].as_mut_ptr(), out[2]);
+                        for (j, p) in dst[i..i + W].iter_mut().enumerate() {
+                            *p = [lanes[0][j], lanes[1][j], lanes[2][j]];
+                        }
+                        i += W;
+                    }
+                    i
+                }
+            }
+        }
+    }
+
+    kernels!(sse, 4, "sse", __m128,
+        _mm_loadu_ps, _mm_storeu_ps, _mm_set1_ps, _mm_setzero_ps, _mm_add_ps, _mm_sub_ps, _mm_mul_ps);
+    kernels!(avx, 8, "avx", __m256,
+        _mm256_loadu_ps, _mm256_storeu_ps, _mm256_set1_ps, _mm256_setzero_ps, _mm256_add_ps, _mm256_sub_ps, _mm256_mul_ps);
+
+    /// Element-wise product of two batches, see `DualQuaternionBatch::mul`
+    pub fn mul(a: &[&[f32]; 8], b: &[&[f32]; 8], d: &mut [&mut [f32]; 8]) {
+        // SSE is always available on x86_64
+        let done = unsafe { if has_avx() { avx::mul(a, b, d) } else { sse::mul(a, b, d) } };
+        for i in done..a[0].len() {
+            write(d, i, scalar_mul(read(a, i), read(b, i)));
+        }
+    }
+
+    /// Transforms each point by the dual-quaternion at the same index, see `transform_point`
+    pub fn transform_points(q: &[&[f32]; 8], src: &[Vector3<f32>], dst: &mut [Vector3<f32>]) {
+        // SSE is always available on x86_64
+        let done = unsafe {
+            if has_avx() { avx::transform_points(q, src, dst) } else { sse::transform_points(q, src, dst) }
+        };
+        for i in done..src.len() {
+            dst[i] = transform_point(read(q, i), src[i]);
+        }
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod test {
+
+    use vecmath::Vector3;
+
+    use test_util::random_poses;
+    use super::DualQuaternionBatch;
+    use super::super::{blend, id, mul, normalize, scale, transform_point};
+
+    #[test]
+    fn test_matches_scalar() {
+        // 19 elements cover the vectorized chunks and the remainder
+        let mut state = 7;
+        let a = random_poses(19, &mut state);
+        let b = random_poses(19, &mut state);
+        let batch_a: DualQuaternionBatch<f32> = a.iter().cloned().collect();
+        let batch_b = DualQuaternionBatch::from_slice(&b);
+        assert_eq!(batch_a.len(), 19);
+
+        let mut product = DualQuaternionBatch::new();
+        batch_a.mul(&batch_b, &mut product);
+        for (i, q) in product.iter().enumerate() {
+            assert_eq!(q, mul(a[i], b[i]));
+        }
+
+        let mut scaled: DualQuaternionBatch<f32> = a.iter().map(|&q| scale(q, 3.0)).collect();
+        scaled.normalize();
+        for (i, q) in scaled.iter().enumerate() {
+            assert_eq!(q, normalize(scale(a[i], 3.0)));
+        }
+
+        let src: std::vec::Vec<Vector3<f32>> = (0..19).map(|i| [i as f32, 1.0 - i as f32, 0.5]).collect();
+        let mut dst = vec![[0.0; 3]; 19];
+        batch_a.transform_points(&src, &mut dst);
+        for (i, p) in dst.iter().enumerate() {
+            assert_eq!(*p, transform_point(a[i], src[i]));
+        }
+    }
+
+    #[test]
+    fn test_blend_matches_scalar() {
+        let mut state = 3;
+        let a = random_poses(13, &mut state);
+        // negated dual-quaternions exercise the hemisphere alignment
+        let b: std::vec::Vec<_> = random_poses(13, &mut state).into_iter().map(|q| scale(q, -1.0)).collect();
+        let mut wa: std::vec::Vec<f32> = (0..13).map(|i| i as f32 / 12.0).collect();
+        let mut wb: std::vec::Vec<f32> = wa.iter().map(|w| 1.0 - w).collect();
+        // zero weights give the identity, in the vectorized chunks and the remainder
+        for &i in &[3, 11] {
+            wa[i] = 0.0;
+            wb[i] = 0.0;
+        }
+
+        let mut blended = DualQuaternionBatch::new();
+        DualQuaternionBatch::blend(&[
+            (&DualQuaternionBatch::from_slice(&a), &wa[..]),
+            (&DualQuaternionBatch::from_slice(&b), &wb[..])
+        ], &mut blended);
+        for (i, q) in blended.iter().enumerate() {
+            assert_eq!(q, blend(&[(a[i], wa[i]), (b[i], wb[i])]));
+        }
+        assert_eq!(blended.get(3), id());
+        assert_eq!(blended.get(11), id());
+
+        DualQuaternionBatch::<f32>::blend(&[], &mut blended);
+        assert!(blended.is_empty());
+    }
+
+}
@@ -0,0 +1,225 @@
+//! Dual numbers of the form `real + ε dual`, where `ε² = 0`
+//!
+//! Dual numbers describe quantities such as the norm of a dual-quaternion
+//! or the angle and translation of a screw motion. Evaluating a function on
+//! `x + ε` also yields its derivative in the dual part, which gives forward-mode
+//! automatic differentiation.
+
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+use vecmath::traits::Float;
+
+use math;
+
+/// A dual number `real + ε dual`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Dual<T> {
+    /// The real part
+    pub real: T,
+    /// The dual part
+    pub dual: T,
+}
+
+impl<T> From<(T, T)> for Dual<T> {
+    #[inline(always)]
+    fn from((real, dual): (T, T)) -> Dual<T> {
+        Dual { real, dual }
+    }
+}
+
+impl<T> From<Dual<T>> for (T, T) {
+    #[inline(always)]
+    fn from(d: Dual<T>) -> (T, T) {
+        (d.real, d.dual)
+    }
+}
+
+impl<T: Float> Dual<T> {
+    /// Constructs a dual number from its real and dual parts
+    #[inline(always)]
+    pub fn new(real: T, dual: T) -> Dual<T> {
+        Dual { real, dual }
+    }
+
+    /// Constructs a dual number with a zero dual part
+    #[inline(always)]
+    pub fn from_real(real: T) -> Dual<T> {
+        Dual { real, dual: T::zero() }
+    }
+
+    /// Constructs the variable `x + ε`, for differentiating with respect to `x`
+    #[inline(always)]
+    pub fn variable(x: T) -> Dual<T> {
+        Dual { real: x, dual: T::one() }
+    }
+
+    /// Returns the dual conjugate, `real - ε dual`
+    #[inline(always)]
+    pub fn conj(self) -> Dual<T> {
+        Dual { real: self.real, dual: -self.dual }
+    }
+
+    /// Returns the reciprocal, the real part must not be zero
+    #[inline(always)]
+    pub fn recip(self) -> Dual<T> {
+        let r = T::one() / self.real;
+        Dual { real: r, dual: -self.dual * r * r }
+    }
+
+    /// Returns the square root, the real part must be positive
+    #[inline(always)]
+    pub fn sqrt(self) -> Dual<T> {
+        let s = math::sqrt(self.real);
+        Dual { real: s, dual: self.dual / (s + s) }
+    }
+
+    /// Returns the sine
+    #[inline(always)]
+    pub fn sin(self) -> Dual<T> {
+        Dual { real: math::sin(self.real), dual: self.dual * math::cos(self.real) }
+    }
+
+    /// Returns the cosine
+    #[inline(always)]
+    pub fn cos(self) -> Dual<T> {
+        Dual { real: math::cos(self.real), dual: -self.dual * math::sin(self.real) }
+    }
+
+    /// Returns the four quadrant arctangent of `self` (y) and `other` (x)
+    #[inline(always)]
+    pub fn atan2(self, other: Dual<T>) -> Dual<T> {
+        let (y, x) = (self, other);
+        Dual {
+            real: math::atan2(y.real, x.real),
+            dual: (x.real * y.dual - y.real * x.dual) / (x.real * x.real + y.real * y.real),
+        }
+    }
+
+    /// Returns `e^self`
+    #[inline(always)]
+    pub fn exp(self) -> Dual<T> {
+        let e = math::exp(self.real);
+        Dual { real: e, dual: self.dual * e }
+    }
+}
+
+impl<T: Float> Add for Dual<T> {
+    type Output = Dual<T>;
+
+    #[inline(always)]
+    fn add(self, other: Dual<T>) -> Dual<T> {
+        Dual { real: self.real + other.real, dual: self.dual + other.dual }
+    }
+}
+
+impl<T: Float> Sub for Dual<T> {
+    type Output = Dual<T>;
+
+    #[inline(always)]
+    fn sub(self, other: Dual<T>) -> Dual<T> {
+        Dual { real: self.real - other.real, dual: self.dual - other.dual }
+    }
+}
+
+impl<T: Float> Mul for Dual<T> {
+    type Output = Dual<T>;
+
+    #[inline(always)]
+    fn mul(self, other: Dual<T>) -> Dual<T> {
+        Dual {
+            real: self.real * other.real,
+            dual: self.real * other.dual + self.dual * other.real,
+        }
+    }
+}
+
+impl<T: Float> Mul<T> for Dual<T> {
+    type Output = Dual<T>;
+
+    #[inline(always)]
+    fn mul(self, t: T) -> Dual<T> {
+        Dual { real: self.real * t, dual: self.dual * t }
+    }
+}
+
+impl<T: Float> Div for Dual<T> {
+    type Output = Dual<T>;
+
+    #[inline(always)]
+    fn div(self, other: Dual<T>) -> Dual<T> {
+        Dual {
+            real: self.real / other.real,
+            dual: (self.dual * other.real - self.real * other.dual) / (other.real * other.real),
+        }
+    }
+}
+
+impl<T: Float> Div<T> for Dual<T> {
+    type Output = Dual<T>;
+
+    #[inline(always)]
+    fn div(self, t: T) -> Dual<T> {
+        Dual { real: self.real / t, dual: self.dual / t }
+    }
+}
+
+impl<T: Float> Neg for Dual<T> {
+    type Output = Dual<T>;
+
+    #[inline(always)]
+    fn neg(self) -> Dual<T> {
+        Dual { real: -self.real, dual: -self.dual }
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod test {
+
+    use super::Dual;
+
+    const EPSILON: f64 = 0.000000001;
+
+    fn assert_dual_eq(a: Dual<f64>, real: f64, dual: f64) {
+        assert!((a.real - real).abs() < EPSILON, "{:?} != ({}, {})", a, real, dual);
+        assert!((a.dual - dual).abs() < EPSILON, "{:?} != ({}, {})", a, real, dual);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Dual::new(2.0, 3.0);
+        let b = Dual::new(-1.0, 0.5);
+        assert_dual_eq(a + b, 1.0, 3.5);
+        assert_dual_eq(a - b, 3.0, 2.5);
+        assert_dual_eq(a * b, -2.0, -2.0);
+        assert_dual_eq((a / b) * b, 2.0, 3.0);
+        assert_dual_eq(a * a.recip(), 1.0, 0.0);
+        assert_dual_eq(-a, -2.0, -3.0);
+        assert_dual_eq(a * 2.0, 4.0, 6.0);
+        assert_dual_eq(a / 2.0, 1.0, 1.5);
+        assert_dual_eq(a * a.conj(), 4.0, 0.0);
+        assert_dual_eq(a.sqrt() * a.sqrt(), 2.0, 3.0);
+    }
+
+    #[test]
+    fn test_derivatives() {
+        // the dual part of f(x + ε) is f'(x)
+        let x = 0.7;
+        let v = Dual::variable(x);
+
+        assert_dual_eq(v.sin(), x.sin(), x.cos());
+        assert_dual_eq(v.cos(), x.cos(), -x.sin());
+        assert_dual_eq(v.exp(), x.exp(), x.exp());
+        assert_dual_eq(v.sqrt(), x.sqrt(), 0.5 / x.sqrt());
+        assert_dual_eq(v.recip(), 1.0 / x, -1.0 / (x * x));
+        assert_dual_eq(v.atan2(Dual::from_real(2.0)), x.atan2(2.0), 2.0 / (4.0 + x * x));
+        assert_dual_eq(Dual::from_real(2.0).atan2(v), 2.0f64.atan2(x), -2.0 / (4.0 + x * x));
+
+        // chain and product rules
+        let f = v.sin() * v.exp() / (v * v + Dual::from_real(1.0));
+        let df = (x.cos() * x.exp() + x.sin() * x.exp()) / (x * x + 1.0)
+            - x.sin() * x.exp() * 2.0 * x / ((x * x + 1.0) * (x * x + 1.0));
+        assert_dual_eq(f, x.sin() * x.exp() / (x * x + 1.0), df);
+    }
+
+}
@@ -0,0 +1,431 @@
+//! A dual-quaternion struct with operator overloads
+//!
+//! `DualQuat<T>` wraps the same data as the `DualQuaternion<T>` tuple,
+//! and converts to and from it with `From`/`Into`.
+//! Its methods mirror the free functions of the crate,
+//! with `add`, `mul` and `scale` available as operators.
+
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use quaternion::Quaternion;
+use vecmath::{Matrix3x4, Matrix4, Vector3};
+use vecmath::traits::Float;
+
+use line::{self, Line};
+use super::{Dual, DualQuaternion, MatrixError, NormalizeError, Screw, Twist};
+
+/// A dual-quaternion with a real and a dual part
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DualQuat<T> {
+    /// The real part, which holds the rotation
+    pub real: Quaternion<T>,
+    /// The dual part, which holds the translation
+    pub dual: Quaternion<T>,
+}
+
+impl<T> From<DualQuaternion<T>> for DualQuat<T> {
+    #[inline(always)]
+    fn from(q: DualQuaternion<T>) -> DualQuat<T> {
+        DualQuat { real: q.0, dual: q.1 }
+    }
+}
+
+impl<T> From<DualQuat<T>> for DualQuaternion<T> {
+    #[inline(always)]
+    fn from(q: DualQuat<T>) -> DualQuaternion<T> {
+        (q.real, q.dual)
+    }
+}
+
+impl<T: Float> DualQuat<T> {
+    /// Constructs a dual-quaternion from its real and dual parts
+    #[inline(always)]
+    pub fn new(real: Quaternion<T>, dual: Quaternion<T>) -> DualQuat<T> {
+        DualQuat { real, dual }
+    }
+
+    /// Constructs identity dual-quaternion, see `id`
+    #[inline(always)]
+    pub fn id() -> DualQuat<T> {
+        super::id().into()
+    }
+
+    /// Constructs a dual-quaternion from rotation and translation, see `from_rotation_and_translation`
+    #[inline(always)]
+    pub fn from_rotation_and_translation(rotation: Quaternion<T>, translation: Vector3<T>) -> DualQuat<T> {
+        super::from_rotation_and_translation(rotation, translation).into()
+    }
+
+    /// Constructs a dual-quaternion from screw parameters, see `from_screw`
+    #[inline(always)]
+    pub fn from_screw(screw: Screw<T>) -> DualQuat<T> {
+        super::from_screw(screw).into()
+    }
+
+    /// Maps a twist to a dual-quaternion, see `exp`
+    #[inline(always)]
+    pub fn exp(twist: Twist<T>) -> DualQuat<T> {
+        super::exp(twist).into()
+    }
+
+    /// Converts a column-major 4x4 matrix, see `from_matrix4`
+    #[inline(always)]
+    pub fn from_matrix4(m: Matrix4<T>) -> Result<DualQuat<T>, MatrixError> {
+        super::from_matrix4(m).map(DualQuat::from)
+    }
+
+    /// Converts a row-major 3x4 matrix, see `from_matrix3x4`
+    #[inline(always)]
+    pub fn from_matrix3x4(m: Matrix3x4<T>) -> Result<DualQuat<T>, MatrixError> {
+        super::from_matrix3x4(m).map(DualQuat::from)
+    }
+
+    /// Blends weighted unit dual-quaternions, see `blend`
+    pub fn blend(influences: &[(DualQuat<T>, T)]) -> DualQuat<T> {
+        super::blend_iter(influences.iter().map(|&(q, w)| (q.into(), w))).into()
+    }
+
+    /// Blends exactly four weighted unit dual-quaternions, see `blend4`
+    #[inline(always)]
+    pub fn blend4(q: [DualQuat<T>; 4], w: [T; 4]) -> DualQuat<T> {
+        super::blend4([q[0].into(), q[1].into(), q[2].into(), q[3].into()], w).into()
+    }
+
+    /// Scales the dual-quaternion (element-wise) by a scalar, see `scale`
+    #[inline(always)]
+    pub fn scale(self, t: T) -> DualQuat<T> {
+        super::scale(self.into(), t).into()
+    }
+
+    /// Returns the dual-quaternion conjugate, see `conj`
+    #[inline(always)]
+    pub fn conj(self) -> DualQuat<T> {
+        super::conj(self.into()).into()
+    }
+
+    /// Returns the dual conjugate, see `dual_conj`
+    #[inline(always)]
+    pub fn dual_conj(self) -> DualQuat<T> {
+        super::dual_conj(self.into()).into()
+    }
+
+    /// Returns the combined conjugate, see `combined_conj`
+    #[inline(always)]
+    pub fn combined_conj(self) -> DualQuat<T> {
+        super::combined_conj(self.into()).into()
+    }
+
+    /// Returns the norm as a dual number, see `norm`
+    #[inline(always)]
+    pub fn norm(self) -> Dual<T> {
+        super::norm(self.into())
+    }
+
+    /// Dot product of two dual-quaternions, see `dot`
+    #[inline(always)]
+    pub fn dot(self, other: DualQuat<T>) -> T {
+        super::dot(self.into(), other.into())
+    }
+
+    /// Normalizes the dual-quaternion, see `normalize`
+    #[inline(always)]
+    pub fn normalize(self) -> DualQuat<T> {
+        super::normalize(self.into()).into()
+    }
+
+    /// Normalizes the dual-quaternion, failing for zero, non-finite or overflowing input, see `try_normalize`
+    #[inline(always)]
+    pub fn try_normalize(self) -> Result<DualQuat<T>, NormalizeError> {
+        super::try_normalize(self.into()).map(DualQuat::from)
+    }
+
+    /// Renormalizes a nearly unit dual-quaternion in place, see `renormalize_in_place`
+    #[inline(always)]
+    pub fn renormalize_in_place(&mut self) {
+        let mut q = (*self).into();
+        super::renormalize_in_place(&mut q);
+        *self = q.into();
+    }
+
+    /// Inverts a unit dual-quaternion, see `inverse`
+    #[inline(always)]
+    pub fn inverse(self) -> DualQuat<T> {
+        super::inverse(self.into()).into()
+    }
+
+    /// Inverts an arbitrary dual-quaternion, see `try_inverse`
+    #[inline(always)]
+    pub fn try_inverse(self) -> Option<DualQuat<T>> {
+        super::try_inverse(self.into()).map(DualQuat::from)
+    }
+
+    /// Returns the relative transform from `self` to `other`, see `between`
+    #[inline(always)]
+    pub fn between(self, other: DualQuat<T>) -> DualQuat<T> {
+        super::between(self.into(), other.into()).into()
+    }
+
+    /// Converts to screw parameters, see `to_screw`
+    #[inline(always)]
+    pub fn to_screw(self) -> Screw<T> {
+        super::to_screw(self.into())
+    }
+
+    /// Maps to the twist that generates the dual-quaternion, see `log`
+    #[inline(always)]
+    pub fn log(self) -> Twist<T> {
+        super::log(self.into())
+    }
+
+    /// Raises a unit dual-quaternion to a real power, see `pow`
+    #[inline(always)]
+    pub fn pow(self, t: T) -> DualQuat<T> {
+        super::pow(self.into(), t).into()
+    }
+
+    /// Returns the square root of a unit dual-quaternion, see `sqrt`
+    #[inline(always)]
+    pub fn sqrt(self) -> DualQuat<T> {
+        super::sqrt(self.into()).into()
+    }
+
+    /// Screw linear interpolation from `self` to `other`, see `sclerp`
+    #[inline(always)]
+    pub fn sclerp(self, other: DualQuat<T>, t: T) -> DualQuat<T> {
+        super::sclerp(self.into(), other.into(), t).into()
+    }
+
+    /// Converts to a column-major 4x4 matrix, see `to_matrix4`
+    #[inline(always)]
+    pub fn to_matrix4(self) -> Matrix4<T> {
+        super::to_matrix4(self.into())
+    }
+
+    /// Converts to a row-major 3x4 matrix, see `to_matrix3x4`
+    #[inline(always)]
+    pub fn to_matrix3x4(self) -> Matrix3x4<T> {
+        super::to_matrix3x4(self.into())
+    }
+
+    /// Extracts the rotation component, see `get_rotation`
+    #[inline(always)]
+    pub fn get_rotation(self) -> Quaternion<T> {
+        super::get_rotation(self.into())
+    }
+
+    /// Extracts the translation component, see `get_translation`
+    #[inline(always)]
+    pub fn get_translation(self) -> Vector3<T> {
+        super::get_translation(self.into())
+    }
+
+    /// Transforms a point, see `transform_point`
+    #[inline(always)]
+    pub fn transform_point(self, p: Vector3<T>) -> Vector3<T> {
+        super::transform_point(self.into(), p)
+    }
+
+    /// Transforms a direction vector, see `transform_vector`
+    #[inline(always)]
+    pub fn transform_vector(self, v: Vector3<T>) -> Vector3<T> {
+        super::transform_vector(self.into(), v)
+    }
+
+    /// Transforms a slice of points, see `transform_points`
+    #[inline(always)]
+    pub fn transform_points(self, src: &[Vector3<T>], dst: &mut [Vector3<T>]) {
+        super::transform_points(self.into(), src, dst)
+    }
+
+    /// Transforms a slice of direction vectors, see `transform_vectors`
+    #[inline(always)]
+    pub fn transform_vectors(self, src: &[Vector3<T>], dst: &mut [Vector3<T>]) {
+        super::transform_vectors(self.into(), src, dst)
+    }
+
+    /// Transforms a line, see `line::transform_line`
+    #[inline(always)]
+    pub fn transform_line(self, l: Line<T>) -> Line<T> {
+        line::transform_line(self.into(), l)
+    }
+}
+
+impl<T: Float> Mul<DualQuat<T>> for DualQuat<T> {
+    type Output = DualQuat<T>;
+
+    #[inline(always)]
+    fn mul(self, other: DualQuat<T>) -> DualQuat<T> {
+        super::mul(self.into(), other.into()).into()
+    }
+}
+
+impl<T: Float> Mul<T> for DualQuat<T> {
+    type Output = DualQuat<T>;
+
+    #[inline(always)]
+    fn mul(self, t: T) -> DualQuat<T> {
+        self.scale(t)
+    }
+}
+
+impl<T: Float> Div<T> for DualQuat<T> {
+    type Output = DualQuat<T>;
+
+    #[inline(always)]
+    fn div(self, t: T) -> DualQuat<T> {
+        self.scale(T::one() / t)
+    }
+}
+
+impl<T: Float> Add for DualQuat<T> {
+    type Output = DualQuat<T>;
+
+    #[inline(always)]
+    fn add(self, other: DualQuat<T>) -> DualQuat<T> {
+        super::add(self.into(), other.into()).into()
+    }
+}
+
+impl<T: Float> Sub for DualQuat<T> {
+    type Output = DualQuat<T>;
+
+    #[inline(always)]
+    fn sub(self, other: DualQuat<T>) -> DualQuat<T> {
+        self + -other
+    }
+}
+
+impl<T: Float> Neg for DualQuat<T> {
+    type Output = DualQuat<T>;
+
+    #[inline(always)]
+    fn neg(self) -> DualQuat<T> {
+        self.scale(-T::one())
+    }
+}
+
+impl<T: Float> MulAssign<DualQuat<T>> for DualQuat<T> {
+    #[inline(always)]
+    fn mul_assign(&mut self, other: DualQuat<T>) {
+        *self = *self * other;
+    }
+}
+
+impl<T: Float> MulAssign<T> for DualQuat<T> {
+    #[inline(always)]
+    fn mul_assign(&mut self, t: T) {
+        *self = *self * t;
+    }
+}
+
+impl<T: Float> DivAssign<T> for DualQuat<T> {
+    #[inline(always)]
+    fn div_assign(&mut self, t: T) {
+        *self = *self / t;
+    }
+}
+
+impl<T: Float> AddAssign for DualQuat<T> {
+    #[inline(always)]
+    fn add_assign(&mut self, other: DualQuat<T>) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Float> SubAssign for DualQuat<T> {
+    #[inline(always)]
+    fn sub_assign(&mut self, other: DualQuat<T>) {
+        *self = *self - other;
+    }
+}
+
+macro_rules! impl_scalar_mul {
+    ($t:ty) => {
+        impl Mul<DualQuat<$t>> for $t {
+            type Output = DualQuat<$t>;
+
+            #[inline(always)]
+            fn mul(self, q: DualQuat<$t>) -> DualQuat<$t> {
+                q.scale(self)
+            }
+        }
+    }
+}
+
+impl_scalar_mul!(f32);
+impl_scalar_mul!(f64);
+
+/// Tests
+#[cfg(test)]
+mod test {
+
+    use std::f64::consts::PI;
+    use quaternion;
+
+    use super::DualQuat;
+    use super::super::{add, from_rotation_and_translation, mul, scale, DualQuaternion};
+
+    const EPSILON: f64 = 0.000000001;
+
+    fn assert_approx_eq(a: DualQuat<f64>, b: DualQuaternion<f64>) {
+        let a: DualQuaternion<f64> = a.into();
+        assert!(((a.0).0 - (b.0).0).abs() < EPSILON);
+        assert!(((a.1).0 - (b.1).0).abs() < EPSILON);
+        for i in 0..3 {
+            assert!(((a.0).1[i] - (b.0).1[i]).abs() < EPSILON);
+            assert!(((a.1).1[i] - (b.1).1[i]).abs() < EPSILON);
+        }
+    }
+
+    #[test]
+    fn test_conversion() {
+        let q = from_rotation_and_translation(quaternion::axis_angle([0.0, 1.0, 0.0], 0.5), [1.0, 2.0, 3.0]);
+        let dq: DualQuat<f64> = q.into();
+        assert_eq!(dq.real, q.0);
+        assert_eq!(dq.dual, q.1);
+        let q_prime: DualQuaternion<f64> = dq.into();
+        assert_eq!(q_prime, q);
+    }
+
+    #[test]
+    fn test_operators() {
+        let a = from_rotation_and_translation(quaternion::axis_angle([0.0, 1.0, 0.0], 0.5), [1.0, 2.0, 3.0]);
+        let b = from_rotation_and_translation(quaternion::axis_angle([1.0, 0.0, 0.0], PI / 3.0), [-1.0, 0.0, 4.0]);
+        let (da, db) = (DualQuat::from(a), DualQuat::from(b));
+
+        assert_approx_eq(da * db, mul(a, b));
+        assert_approx_eq(da + db, add(a, b));
+        assert_approx_eq(da - db, add(a, scale(b, -1.0)));
+        assert_approx_eq(-da, scale(a, -1.0));
+        assert_approx_eq(da * 2.0, scale(a, 2.0));
+        assert_approx_eq(2.0 * da, scale(a, 2.0));
+        assert_approx_eq(da / 2.0, scale(a, 0.5));
+
+        let mut dq = da;
+        dq *= db;
+        assert_approx_eq(dq, mul(a, b));
+        dq += da;
+        assert_approx_eq(dq, add(mul(a, b), a));
+        dq -= da;
+        assert_approx_eq(dq, mul(a, b));
+        dq *= 4.0;
+        dq /= 2.0;
+        assert_approx_eq(dq, scale(mul(a, b), 2.0));
+    }
+
+    #[test]
+    fn test_methods() {
+        let a = from_rotation_and_translation(quaternion::axis_angle([0.0, 0.0, 1.0], 1.2), [1.0, -2.0, 0.5]);
+        let b = from_rotation_and_translation(quaternion::axis_angle([0.0, 1.0, 0.0], -0.7), [0.0, 3.0, 1.0]);
+        let (da, db) = (DualQuat::from(a), DualQuat::from(b));
+
+        assert_approx_eq(da.inverse(), super::super::inverse(a));
+        assert_approx_eq(da.between(db), super::super::between(a, b));
+        assert_approx_eq(da.sclerp(db, 0.3), super::super::sclerp(a, b, 0.3));
+        assert_approx_eq(DualQuat::blend(&[(da, 0.25), (db, 0.75)]), super::super::blend(&[(a, 0.25), (b, 0.75)]));
+        assert_approx_eq(da.inverse() * da, super::super::id());
+        assert_eq!(da.transform_point([1.0, 1.0, 1.0]), super::super::transform_point(a, [1.0, 1.0, 1.0]));
+    }
+
+}
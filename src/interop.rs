@@ -0,0 +1,240 @@
+//! Conversions to and from other math libraries, enabled by cargo features
+//!
+//! Every conversion goes through a `mint` rotation and translation pair where the other
+//! library supports `mint`. Conversions into other types expect a unit dual-quaternion.
+//! Conversions from types that can hold scale or shear, such as `glam::Affine3A`
+//! and `cgmath::Decomposed`, use `TryFrom` and fail with a `MatrixError`
+//! when the value is not a rigid transform.
+//!
+//! The `DualQuaternion<T>` tuple converts through `DualQuat<T>`.
+
+use mint;
+use vecmath::traits::Float;
+
+use super::{from_rotation_and_translation, get_rotation, get_translation, DualQuat};
+
+impl<T: Float> From<DualQuat<T>> for (mint::Quaternion<T>, mint::Vector3<T>) {
+    /// Splits a unit dual-quaternion into its rotation and translation
+    #[inline(always)]
+    fn from(q: DualQuat<T>) -> (mint::Quaternion<T>, mint::Vector3<T>) {
+        let q = q.into();
+        let (s, v) = get_rotation(q);
+        (mint::Quaternion { s, v: v.into() }, get_translation(q).into())
+    }
+}
+
+impl<T: Float> From<(mint::Quaternion<T>, mint::Vector3<T>)> for DualQuat<T> {
+    /// Constructs a dual-quaternion from a unit rotation and a translation
+    #[inline(always)]
+    fn from((r, t): (mint::Quaternion<T>, mint::Vector3<T>)) -> DualQuat<T> {
+        from_rotation_and_translation((r.s, r.v.into()), t.into()).into()
+    }
+}
+
+#[cfg(feature = "glam")]
+mod glam_impl {
+    use core::convert::TryFrom;
+
+    use glam::{Affine3A, DAffine3, DQuat, DVec3, Quat, Vec3};
+    use mint;
+
+    use super::super::{from_matrix3x4, DualQuat, MatrixError};
+
+    macro_rules! impl_glam {
+        ($t:ty, $affine:ty, $quat:ty, $vec3:ty) => {
+            impl From<DualQuat<$t>> for $affine {
+                #[inline(always)]
+                fn from(q: DualQuat<$t>) -> $affine {
+                    let (r, t): (mint::Quaternion<$t>, mint::Vector3<$t>) = q.into();
+                    <$affine>::from_rotation_translation(<$quat>::from(r), <$vec3>::from(t))
+                }
+            }
+
+            impl TryFrom<$affine> for DualQuat<$t> {
+                type Error = MatrixError;
+
+                fn try_from(a: $affine) -> Result<DualQuat<$t>, MatrixError> {
+                    let c = a.to_cols_array_2d();
+                    from_matrix3x4([
+                        [c[0][0], c[1][0], c[2][0], c[3][0]],
+                        [c[0][1], c[1][1], c[2][1], c[3][1]],
+                        [c[0][2], c[1][2], c[2][2], c[3][2]]
+                    ]).map(DualQuat::from)
+                }
+            }
+        }
+    }
+
+    impl_glam!(f32, Affine3A, Quat, Vec3);
+    impl_glam!(f64, DAffine3, DQuat, DVec3);
+}
+
+#[cfg(feature = "nalgebra")]
+mod nalgebra_impl {
+    use mint;
+    use nalgebra::{Isometry3, Quaternion, RealField, Translation3, UnitQuaternion, Vector3};
+    use vecmath::traits::Float;
+
+    use super::super::DualQuat;
+
+    impl<T: Float + RealField> From<DualQuat<T>> for Isometry3<T> {
+        #[inline(always)]
+        fn from(q: DualQuat<T>) -> Isometry3<T> {
+            let (r, t): (mint::Quaternion<T>, mint::Vector3<T>) = q.into();
+            Isometry3::from_parts(
+                Translation3::from(Vector3::from(t)),
+                UnitQuaternion::new_unchecked(Quaternion::from(r))
+            )
+        }
+    }
+
+    impl<T: Float + RealField> From<Isometry3<T>> for DualQuat<T> {
+        #[inline(always)]
+        fn from(iso: Isometry3<T>) -> DualQuat<T> {
+            let r: mint::Quaternion<T> = iso.rotation.into();
+            let t: mint::Vector3<T> = iso.translation.vector.into();
+            (r, t).into()
+        }
+    }
+}
+
+#[cfg(feature = "cgmath")]
+mod cgmath_impl {
+    use core::convert::TryFrom;
+
+    use cgmath::{BaseFloat, Decomposed, InnerSpace, Quaternion, Vector3};
+    use mint;
+    use vecmath::traits::{Float, One};
+
+    use super::super::{is_finite, matrix_tolerance, within, DualQuat, MatrixError};
+
+    impl<T: Float + BaseFloat> From<DualQuat<T>> for Decomposed<Vector3<T>, Quaternion<T>> {
+        #[inline(always)]
+        fn from(q: DualQuat<T>) -> Decomposed<Vector3<T>, Quaternion<T>> {
+            let (r, t): (mint::Quaternion<T>, mint::Vector3<T>) = q.into();
+            Decomposed {
+                scale: <T as One>::one(),
+                rot: r.into(),
+                disp: t.into(),
+            }
+        }
+    }
+
+    impl<T: Float + BaseFloat> TryFrom<Decomposed<Vector3<T>, Quaternion<T>>> for DualQuat<T> {
+        type Error = MatrixError;
+
+        /// Fails if the scale isn't one or the rotation isn't a unit quaternion,
+        /// within a tolerance of `1e-4`, or if the displacement isn't finite
+        fn try_from(d: Decomposed<Vector3<T>, Quaternion<T>>) -> Result<DualQuat<T>, MatrixError> {
+            let tolerance = matrix_tolerance();
+            if !within(d.scale - <T as One>::one(), tolerance)
+                || !within(d.rot.magnitude2() - <T as One>::one(), tolerance) {
+                return Err(MatrixError::NotOrthonormal);
+            }
+            if !(is_finite(d.disp.x) && is_finite(d.disp.y) && is_finite(d.disp.z)) {
+                return Err(MatrixError::NonFinite);
+            }
+            let r: mint::Quaternion<T> = d.rot.into();
+            let t: mint::Vector3<T> = d.disp.into();
+            Ok((r, t).into())
+        }
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod test {
+
+    use quaternion;
+    use vecmath::{self, Vector3};
+
+    use super::super::{from_rotation_and_translation, transform_point, DualQuat, DualQuaternion};
+
+    fn pose() -> DualQuaternion<f64> {
+        from_rotation_and_translation(
+            quaternion::axis_angle(vecmath::vec3_normalized([1.0, -2.0, 0.5]), 2.3), [3.0, -1.0, 0.25]
+        )
+    }
+
+    fn assert_same_transform(a: DualQuaternion<f64>, b: DualQuaternion<f64>, eps: f64) {
+        let p: Vector3<f64> = [0.5, 2.0, -3.0];
+        let (pa, pb) = (transform_point(a, p), transform_point(b, p));
+        for i in 0..3 {
+            assert!((pa[i] - pb[i]).abs() < eps, "{:?} != {:?}", pa, pb);
+        }
+    }
+
+    #[test]
+    fn test_mint_round_trip() {
+        use mint;
+
+        let q = DualQuat::from(pose());
+        let (r, t): (mint::Quaternion<f64>, mint::Vector3<f64>) = q.into();
+        assert!((t.x - 3.0).abs() < 1e-12 && (t.y + 1.0).abs() < 1e-12 && (t.z - 0.25).abs() < 1e-12);
+        assert_same_transform(q.into(), DualQuat::from((r, t)).into(), 1e-12);
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn test_glam_round_trip() {
+        use std::convert::TryFrom;
+        use glam::{Affine3A, DAffine3, Vec3};
+
+        use super::super::MatrixError;
+
+        let q = DualQuat::from(pose());
+        let a = DAffine3::from(q);
+        let p = a.transform_point3([0.5, 2.0, -3.0].into());
+        assert_same_transform(q.into(), DualQuat::try_from(a).unwrap().into(), 1e-12);
+        assert!((p.x - transform_point(q.into(), [0.5, 2.0, -3.0])[0]).abs() < 1e-12);
+
+        let q32: DualQuat<f32> = DualQuat::new((1.0, [0.0, 0.0, 0.0]), (0.0, [1.0, 2.0, 3.0]));
+        assert_eq!(DualQuat::try_from(Affine3A::from(q32)).unwrap(), q32);
+
+        let scaled = Affine3A::from_scale(Vec3::new(2.0, 1.0, 1.0));
+        assert_eq!(DualQuat::try_from(scaled), Err(MatrixError::NotOrthonormal));
+    }
+
+    #[cfg(feature = "nalgebra")]
+    #[test]
+    fn test_nalgebra_round_trip() {
+        use nalgebra::{Isometry3, Point3};
+
+        let q = DualQuat::from(pose());
+        let iso = Isometry3::from(q);
+        let p = iso.transform_point(&Point3::new(0.5, 2.0, -3.0));
+        let expected = transform_point(q.into(), [0.5, 2.0, -3.0]);
+        for i in 0..3 {
+            assert!((p[i] - expected[i]).abs() < 1e-12);
+        }
+        assert_same_transform(q.into(), DualQuat::from(iso).into(), 1e-12);
+    }
+
+    #[cfg(feature = "cgmath")]
+    #[test]
+    fn test_cgmath_round_trip() {
+        use std::convert::TryFrom;
+        use cgmath::{Decomposed, Point3, Quaternion, Transform, Vector3};
+
+        use super::super::MatrixError;
+
+        let q = DualQuat::from(pose());
+        let d: Decomposed<Vector3<f64>, Quaternion<f64>> = q.into();
+        let p = d.transform_point(Point3::new(0.5, 2.0, -3.0));
+        let expected = transform_point(q.into(), [0.5, 2.0, -3.0]);
+        assert!((p.x - expected[0]).abs() < 1e-12);
+        assert!((p.y - expected[1]).abs() < 1e-12);
+        assert!((p.z - expected[2]).abs() < 1e-12);
+        assert_same_transform(q.into(), DualQuat::try_from(d).unwrap().into(), 1e-12);
+
+        let scaled = Decomposed { scale: 2.0, ..d };
+        assert_eq!(DualQuat::try_from(scaled), Err(MatrixError::NotOrthonormal));
+        let nan = Decomposed { scale: f64::NAN, ..d };
+        assert_eq!(DualQuat::try_from(nan), Err(MatrixError::NotOrthonormal));
+        let nan = Decomposed { rot: Quaternion::new(f64::NAN, 0.0, 0.0, 0.0), ..d };
+        assert_eq!(DualQuat::try_from(nan), Err(MatrixError::NotOrthonormal));
+        let nan = Decomposed { disp: Vector3::new(0.0, f64::NAN, 0.0), ..d };
+        assert_eq!(DualQuat::try_from(nan), Err(MatrixError::NonFinite));
+    }
+
+}
@@ -1,16 +1,79 @@
 //! A simple and type agnostic dual-quaternion math library designed for reexporting
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "std")]
+extern crate core;
+#[cfg(all(test, not(feature = "std")))]
+#[macro_use]
+extern crate std;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+extern crate libm;
 extern crate vecmath;
 extern crate quaternion;
+#[cfg(feature = "mint")]
+extern crate mint;
+#[cfg(feature = "glam")]
+extern crate glam;
+#[cfg(feature = "nalgebra")]
+extern crate nalgebra;
+#[cfg(feature = "cgmath")]
+extern crate cgmath;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+
+#[cfg(feature = "std")]
+use std::error::Error;
+use core::fmt;
 
 use quaternion::Quaternion;
-use vecmath::Vector3;
+use vecmath::{Matrix3, Matrix3x4, Matrix4, Vector3};
 use vecmath::traits::Float;
 
+#[cfg(feature = "alloc")]
+pub mod animation;
+pub mod average;
+#[cfg(feature = "alloc")]
+pub mod batch;
+pub mod dual;
+pub mod dual_quat;
+#[cfg(feature = "mint")]
+mod interop;
+pub mod line;
+mod math;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+#[cfg(feature = "serde")]
+pub mod serialization;
+#[cfg(feature = "alloc")]
+pub mod skeleton;
+pub mod skinning;
+pub mod spline;
+#[cfg(test)]
+mod test_util;
+pub mod unit;
+
+#[cfg(feature = "alloc")]
+pub use batch::DualQuaternionBatch;
+pub use dual::Dual;
+pub use dual_quat::DualQuat;
+#[cfg(feature = "alloc")]
+pub use skeleton::Skeleton;
+pub use unit::UnitDualQuaternion;
+
 /// A dual-quaternion consists of a real component and a dual component,
 /// and can be used to represent both rotation and translation
 pub type DualQuaternion<T> = (Quaternion<T>, Quaternion<T>);
 
+/// A twist in se(3), consisting of an angular velocity and a linear velocity
+pub type Twist<T> = (Vector3<T>, Vector3<T>);
+
 /// Constructs identity dual-quaternion, representing no rotation or translation.
 #[inline(always)]
 pub fn id<T: Float>() -> DualQuaternion<T> {
@@ -65,6 +128,9 @@ pub fn scale<T: Float>(q: DualQuaternion<T>, t: T) -> DualQuaternion<T>
 }
 
 /// Returns the dual-quaternion conjugate
+///
+/// This applies the quaternion conjugate to both parts, `q* = r* + ε d*`.
+/// It inverts unit dual-quaternions and is used to transform lines.
 #[inline(always)]
 pub fn conj<T: Float>(q: DualQuaternion<T>) -> DualQuaternion<T> {
     (
@@ -73,6 +139,33 @@ pub fn conj<T: Float>(q: DualQuaternion<T>) -> DualQuaternion<T> {
     )
 }
 
+/// Returns the dual conjugate, which negates the dual part, `r - ε d`
+#[inline(always)]
+pub fn dual_conj<T: Float>(q: DualQuaternion<T>) -> DualQuaternion<T> {
+    (q.0, quaternion::scale(q.1, -T::one()))
+}
+
+/// Returns the combined conjugate, which applies both the quaternion and dual conjugates, `r* - ε d*`
+///
+/// A point `p` embedded as `1 + ε p` is transformed by `q (1 + ε p) combined_conj(q)`.
+#[inline(always)]
+pub fn combined_conj<T: Float>(q: DualQuaternion<T>) -> DualQuaternion<T> {
+    (
+        quaternion::conj(q.0),
+        quaternion::scale(quaternion::conj(q.1), -T::one())
+    )
+}
+
+/// Returns the norm of a dual-quaternion as a dual number
+///
+/// The norm is the square root of `q q*`. Unlike `dot`, it accounts for the dual part:
+/// unit dual-quaternions have a norm of exactly `1 + ε 0`.
+/// The real part must not be zero.
+pub fn norm<T: Float>(q: DualQuaternion<T>) -> Dual<T> {
+    let two = T::from_f64(2.0);
+    Dual::new(dot(q, q), two * quaternion::dot(q.0, q.1)).sqrt()
+}
+
 /// Dot product of two dual-quaternions
 #[inline(always)]
 pub fn dot<T: Float>(a: DualQuaternion<T>, b: DualQuaternion<T>) -> T {
@@ -80,17 +173,516 @@ pub fn dot<T: Float>(a: DualQuaternion<T>, b: DualQuaternion<T>) -> T {
 }
 
 /// Normalizes a dual-quaternion
+///
+/// The result is the nearest unit dual-quaternion: the real part has unit length
+/// and the dual part is orthogonal to it. The real part must not be zero,
+/// use `try_normalize` when that isn't known.
 pub fn normalize<T: Float>(q: DualQuaternion<T>) -> DualQuaternion<T> {
-    let real_len_recip = T::one() / dot(q, q).sqrt();
+    let real_len_recip = T::one() / math::sqrt(dot(q, q));
+    let real = quaternion::scale(q.0, real_len_recip);
+    let dual = quaternion::scale(q.1, real_len_recip);
     (
-        quaternion::scale(q.0, real_len_recip),
-        quaternion::add(
-            quaternion::scale(q.1, real_len_recip),
-            quaternion::scale(q.0, -quaternion::dot(q.0, q.1))
+        real,
+        quaternion::add(dual, quaternion::scale(real, -quaternion::dot(real, dual)))
+    )
+}
+
+/// An error returned when a dual-quaternion can't be normalized
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NormalizeError {
+    /// The real part is zero
+    Zero,
+    /// A component is infinite or NaN
+    NonFinite,
+    /// The components are finite, but the normalized dual part is too large to represent
+    Overflow,
+}
+
+impl fmt::Display for NormalizeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            NormalizeError::Zero => "real part of dual-quaternion is zero",
+            NormalizeError::NonFinite => "dual-quaternion has an infinite or NaN component",
+            NormalizeError::Overflow => "normalized dual part of dual-quaternion overflows",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for NormalizeError {}
+
+/// Normalizes a dual-quaternion, failing if the real part is zero or a component is not finite
+///
+/// The components are divided by the largest one of the real part first, so that
+/// finite inputs don't overflow when squared.
+pub fn try_normalize<T: Float>(q: DualQuaternion<T>) -> Result<DualQuaternion<T>, NormalizeError> {
+    let ((a, [b, c, d]), (e, [f, g, h])) = q;
+    if ![a, b, c, d, e, f, g, h].iter().all(|&x| is_finite(x)) {
+        return Err(NormalizeError::NonFinite);
+    }
+    let largest = [a, b, c, d].iter().fold(T::zero(), |m, &x| m.max(abs(x)));
+    if largest == T::zero() {
+        return Err(NormalizeError::Zero);
+    }
+    let s = |x: T| x / largest;
+    let q = normalize(((s(a), [s(b), s(c), s(d)]), (s(e), [s(f), s(g), s(h)])));
+    // the dual part can still overflow when it is much larger than the real part
+    let ((a, [b, c, d]), (e, [f, g, h])) = q;
+    if ![a, b, c, d, e, f, g, h].iter().all(|&x| is_finite(x)) {
+        return Err(NormalizeError::Overflow);
+    }
+    Ok(q)
+}
+
+/// Returns `true` if a scalar is neither infinite nor NaN
+#[inline(always)]
+fn is_finite<T: Float>(x: T) -> bool {
+    // multiplying infinity or NaN by zero gives NaN, which is not equal to zero
+    x * T::zero() == T::zero()
+}
+
+/// Renormalizes a nearly unit dual-quaternion in place
+///
+/// This is cheaper than `normalize`, avoiding the square root and division,
+/// and is meant to be called after each step of a long chain of compositions.
+/// Each call roughly doubles the number of correct digits, so it converges quickly
+/// when called repeatedly but does not fix inputs that are far from unit length.
+#[inline(always)]
+pub fn renormalize_in_place<T: Float>(q: &mut DualQuaternion<T>) {
+    // one Newton step for `1 / sqrt(x)` around `x = 1`
+    let half = T::from_f64(0.5);
+    let s = half * (T::from_f64(3.0) - dot(*q, *q));
+    let real = quaternion::scale(q.0, s);
+    let dual = quaternion::scale(q.1, s);
+    *q = (
+        real,
+        quaternion::add(dual, quaternion::scale(real, -quaternion::dot(real, dual)))
+    );
+}
+
+/// Inverts a unit dual-quaternion
+///
+/// For unit dual-quaternions the inverse is equal to the conjugate.
+/// Use `try_inverse` for dual-quaternions that are not normalized.
+#[inline(always)]
+pub fn inverse<T: Float>(q: DualQuaternion<T>) -> DualQuaternion<T> {
+    conj(q)
+}
+
+/// Inverts an arbitrary dual-quaternion, returning `None` when the real part is zero
+pub fn try_inverse<T: Float>(q: DualQuaternion<T>) -> Option<DualQuaternion<T>> {
+    let real_len_sq = dot(q, q);
+    if real_len_sq == T::zero() {
+        return None;
+    }
+    let real_inv = quaternion::scale(quaternion::conj(q.0), T::one() / real_len_sq);
+    Some((
+        real_inv,
+        quaternion::scale(
+            quaternion::mul(quaternion::mul(real_inv, q.1), real_inv),
+            -T::one()
+        )
+    ))
+}
+
+/// Returns the relative transform from pose `a` to pose `b`
+///
+/// The result is expressed in the frame of `a`, so that `mul(a, between(a, b))` equals `b`.
+/// Both inputs are assumed to be unit dual-quaternions.
+#[inline(always)]
+pub fn between<T: Float>(a: DualQuaternion<T>, b: DualQuaternion<T>) -> DualQuaternion<T> {
+    mul(inverse(a), b)
+}
+
+/// Screw parameters of a rigid motion, following Chasles' theorem:
+/// a rotation by `angle` about a line, combined with a translation by `translation` along it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Screw<T> {
+    /// Unit direction of the screw axis
+    pub axis: Vector3<T>,
+    /// Moment of the screw axis, `p x axis` for any point `p` on the axis
+    pub moment: Vector3<T>,
+    /// Rotation angle about the axis, in radians
+    pub angle: T,
+    /// Translation distance along the axis
+    pub translation: T,
+}
+
+impl<T: Float> Screw<T> {
+    /// Returns the point on the screw axis closest to the origin
+    pub fn point(&self) -> Vector3<T> {
+        vecmath::vec3_cross(self.axis, self.moment)
+    }
+
+    /// Returns the pitch (translation per radian of rotation), which is infinite for a pure translation
+    /// and NaN for the identity, where both the translation and the angle are zero
+    pub fn pitch(&self) -> T {
+        self.translation / self.angle
+    }
+
+    /// Returns the dual angle `angle + ε translation`
+    pub fn dual_angle(&self) -> Dual<T> {
+        Dual::new(self.angle, self.translation)
+    }
+}
+
+/// Converts a unit dual-quaternion to screw parameters
+///
+/// Motions without rotation, or with a rotation too small for the moment to be representable,
+/// are treated as pure translations: the angle and moment are zero and the axis is the direction of translation.
+/// The identity has an arbitrary axis of `[0, 0, 1]`.
+pub fn to_screw<T: Float>(q: DualQuaternion<T>) -> Screw<T> {
+    let zero = T::zero();
+    let one = T::one();
+    let half = T::from_f64(0.5);
+
+    let sin_half_angle = math::vec3_len((q.0).1);
+    let cos_half_angle = (q.0).0;
+    let translation = get_translation(q);
+    // the moment scales with the cotangent, which only breaks down at or extremely near zero rotation
+    let cot_half_angle = cos_half_angle / sin_half_angle;
+    if sin_half_angle == zero || !is_finite(cot_half_angle) {
+        let distance = math::vec3_len(translation);
+        let axis = if distance > zero {
+            vecmath::vec3_scale(translation, one / distance)
+        } else {
+            [zero, zero, one]
+        };
+        return Screw {
+            axis,
+            moment: [zero, zero, zero],
+            angle: zero,
+            translation: distance,
+        };
+    }
+
+    let v = (q.0).1;
+    let axis = [v[0] / sin_half_angle, v[1] / sin_half_angle, v[2] / sin_half_angle];
+    let distance = vecmath::vec3_dot(translation, axis);
+    let moment = vecmath::vec3_scale(
+        vecmath::vec3_add(
+            vecmath::vec3_cross(translation, axis),
+            vecmath::vec3_scale(
+                vecmath::vec3_sub(translation, vecmath::vec3_scale(axis, distance)),
+                cot_half_angle
+            )
+        ),
+        half
+    );
+    Screw {
+        axis,
+        moment,
+        angle: math::atan2(sin_half_angle, cos_half_angle) * T::from_f64(2.0),
+        translation: distance,
+    }
+}
+
+/// Constructs a unit dual-quaternion from screw parameters
+pub fn from_screw<T: Float>(screw: Screw<T>) -> DualQuaternion<T> {
+    // cos(θ/2) + sin(θ/2) (axis + ε moment), with the dual angle θ = angle + ε translation
+    let half_angle = screw.dual_angle() * T::from_f64(0.5);
+    let (sin, cos) = (half_angle.sin(), half_angle.cos());
+    (
+        (cos.real, vecmath::vec3_scale(screw.axis, sin.real)),
+        (
+            cos.dual,
+            vecmath::vec3_add(
+                vecmath::vec3_scale(screw.moment, sin.real),
+                vecmath::vec3_scale(screw.axis, sin.dual)
+            )
+        )
+    )
+}
+
+/// Maps a twist to the unit dual-quaternion of the rigid motion it generates in unit time
+///
+/// This is the exponential map from se(3) to unit dual-quaternions.
+pub fn exp<T: Float>(twist: Twist<T>) -> DualQuaternion<T> {
+    let half = T::from_f64(0.5);
+    let a = vecmath::vec3_scale(twist.0, half);
+    let b = vecmath::vec3_scale(twist.1, half);
+    let half_angle = math::vec3_len(a);
+    let (sinc, cosc) = sinc_and_cosc(half_angle);
+    let ab = vecmath::vec3_dot(a, b);
+    (
+        (math::cos(half_angle), vecmath::vec3_scale(a, sinc)),
+        (
+            -sinc * ab,
+            vecmath::vec3_add(vecmath::vec3_scale(b, sinc), vecmath::vec3_scale(a, cosc * ab))
         )
     )
 }
 
+/// Maps a unit dual-quaternion to the twist that generates it, the inverse of `exp`
+///
+/// This is the logarithmic map from unit dual-quaternions to se(3).
+/// `q` and `-q` are the same rigid transform and give the same twist,
+/// the one with a rotation angle of at most π.
+pub fn log<T: Float>(q: DualQuaternion<T>) -> Twist<T> {
+    let two = T::from_f64(2.0);
+    // a negative real scalar would give a half angle near π, where `sinc` vanishes
+    let q = if (q.0).0 < T::zero() { scale(q, -T::one()) } else { q };
+    let half_angle = math::atan2(math::vec3_len((q.0).1), (q.0).0);
+    let (sinc, cosc) = sinc_and_cosc(half_angle);
+    let a = vecmath::vec3_scale((q.0).1, T::one() / sinc);
+    let ab = -(q.1).0 / sinc;
+    let b = vecmath::vec3_scale(
+        vecmath::vec3_sub((q.1).1, vecmath::vec3_scale(a, cosc * ab)),
+        T::one() / sinc
+    );
+    (vecmath::vec3_scale(a, two), vecmath::vec3_scale(b, two))
+}
+
+/// Raises a unit dual-quaternion to a real power
+///
+/// This scales both the rotation angle and the translation along the screw axis by `t`.
+/// `q` and `-q` give the same result, scaling the rotation of at most π, see `log`.
+pub fn pow<T: Float>(q: DualQuaternion<T>, t: T) -> DualQuaternion<T> {
+    let twist = log(q);
+    exp((vecmath::vec3_scale(twist.0, t), vecmath::vec3_scale(twist.1, t)))
+}
+
+/// Returns the square root of a unit dual-quaternion, the transform that applied twice gives `q`
+#[inline(always)]
+pub fn sqrt<T: Float>(q: DualQuaternion<T>) -> DualQuaternion<T> {
+    pow(q, T::from_f64(0.5))
+}
+
+/// Returns `sin(x) / x` and `(cos(x) - sin(x) / x) / x^2`,
+/// using Taylor series near zero so that both stay finite and smooth
+fn sinc_and_cosc<T: Float>(x: T) -> (T, T) {
+    let x2 = x * x;
+    if x < T::from_f64(1e-2) {
+        let sinc = T::one() - x2 / T::from_f64(6.0) + x2 * x2 / T::from_f64(120.0);
+        let cosc = -T::one() / T::from_f64(3.0) + x2 / T::from_f64(30.0) - x2 * x2 / T::from_f64(840.0);
+        (sinc, cosc)
+    } else {
+        let sinc = math::sin(x) / x;
+        (sinc, (math::cos(x) - sinc) / x2)
+    }
+}
+
+/// Screw linear interpolation between two unit dual-quaternions
+///
+/// Moves from `a` (at `t = 0`) to `b` (at `t = 1`) along a constant screw motion,
+/// taking the shortest path. Pure translations and near-identity relative motions,
+/// where the screw axis is undefined, reduce to linear interpolation of the translation.
+pub fn sclerp<T: Float>(a: DualQuaternion<T>, b: DualQuaternion<T>, t: T) -> DualQuaternion<T> {
+    let b = if dot(a, b) < T::zero() { scale(b, -T::one()) } else { b };
+    mul(a, pow(between(a, b), t))
+}
+
+/// Blends weighted unit dual-quaternions using dual-quaternion linear blending (DLB)
+///
+/// Each influence is flipped into the hemisphere of the first one before summing,
+/// so the first influence should be the dominant one. The weighted sum is then normalized,
+/// which always yields a rigid transform. Weights are expected to be non-negative.
+/// An empty slice, or weights that cancel the real part such as all zero weights, yield the identity.
+pub fn blend<T: Float>(influences: &[(DualQuaternion<T>, T)]) -> DualQuaternion<T> {
+    blend_iter(influences.iter().cloned())
+}
+
+/// Blends weighted unit dual-quaternions from an iterator, see `blend`
+fn blend_iter<T: Float, I>(mut influences: I) -> DualQuaternion<T>
+    where I: Iterator<Item = (DualQuaternion<T>, T)>
+{
+    let (pivot, w) = match influences.next() {
+        Some(first) => first,
+        None => return id(),
+    };
+    let zero = T::zero();
+    let mut sum = scale(pivot, w);
+    for (q, w) in influences {
+        let w = if dot(pivot, q) < zero { -w } else { w };
+        sum = add(sum, scale(q, w));
+    }
+    normalize_blended(sum)
+}
+
+/// Normalizes a weighted sum of dual-quaternions, yielding the identity if its real part is zero
+#[inline(always)]
+fn normalize_blended<T: Float>(sum: DualQuaternion<T>) -> DualQuaternion<T> {
+    if dot(sum, sum) == T::zero() { id() } else { normalize(sum) }
+}
+
+/// Blends exactly four weighted unit dual-quaternions, see `blend`
+#[inline(always)]
+pub fn blend4<T: Float>(q: [DualQuaternion<T>; 4], w: [T; 4]) -> DualQuaternion<T> {
+    let zero = T::zero();
+    let w1 = if dot(q[0], q[1]) < zero { -w[1] } else { w[1] };
+    let w2 = if dot(q[0], q[2]) < zero { -w[2] } else { w[2] };
+    let w3 = if dot(q[0], q[3]) < zero { -w[3] } else { w[3] };
+    normalize_blended(add(
+        add(scale(q[0], w[0]), scale(q[1], w1)),
+        add(scale(q[2], w2), scale(q[3], w3))
+    ))
+}
+
+/// An error returned when converting a matrix that is not a rigid transform
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatrixError {
+    /// The upper 3x3 part is not orthonormal
+    NotOrthonormal,
+    /// The upper 3x3 part is orthonormal, but contains a reflection
+    Reflection,
+    /// The bottom row of a 4x4 matrix is not `[0, 0, 0, 1]`
+    NotAffine,
+    /// The translation has an infinite or NaN component
+    NonFinite,
+}
+
+impl fmt::Display for MatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            MatrixError::NotOrthonormal => "rotation part of matrix is not orthonormal",
+            MatrixError::Reflection => "rotation part of matrix contains a reflection",
+            MatrixError::NotAffine => "bottom row of matrix is not [0, 0, 0, 1]",
+            MatrixError::NonFinite => "translation part of matrix has an infinite or NaN component",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for MatrixError {}
+
+/// Converts a unit dual-quaternion to a column-major 4x4 homogeneous matrix,
+/// as used by the `vecmath::col_mat4_*` functions
+pub fn to_matrix4<T: Float>(q: DualQuaternion<T>) -> Matrix4<T> {
+    let zero = T::zero();
+    let r = rotation_matrix(q.0);
+    let t = get_translation(q);
+    [
+        [r[0][0], r[1][0], r[2][0], zero],
+        [r[0][1], r[1][1], r[2][1], zero],
+        [r[0][2], r[1][2], r[2][2], zero],
+        [t[0], t[1], t[2], T::one()]
+    ]
+}
+
+/// Converts a unit dual-quaternion to a row-major 3x4 matrix,
+/// as used by the `vecmath::row_mat3x4_*` functions
+pub fn to_matrix3x4<T: Float>(q: DualQuaternion<T>) -> Matrix3x4<T> {
+    let r = rotation_matrix(q.0);
+    let t = get_translation(q);
+    [
+        [r[0][0], r[0][1], r[0][2], t[0]],
+        [r[1][0], r[1][1], r[1][2], t[1]],
+        [r[2][0], r[2][1], r[2][2], t[2]]
+    ]
+}
+
+/// Converts a column-major 4x4 homogeneous matrix to a unit dual-quaternion
+///
+/// Fails if the matrix is not a rigid transform, within a tolerance of `1e-4`.
+pub fn from_matrix4<T: Float>(m: Matrix4<T>) -> Result<DualQuaternion<T>, MatrixError> {
+    let tolerance = matrix_tolerance();
+    if !(within(m[0][3], tolerance) && within(m[1][3], tolerance) &&
+        within(m[2][3], tolerance) && within(m[3][3] - T::one(), tolerance)) {
+        return Err(MatrixError::NotAffine);
+    }
+    from_rotation_matrix_and_translation(
+        [
+            [m[0][0], m[1][0], m[2][0]],
+            [m[0][1], m[1][1], m[2][1]],
+            [m[0][2], m[1][2], m[2][2]]
+        ],
+        [m[3][0], m[3][1], m[3][2]]
+    )
+}
+
+/// Converts a row-major 3x4 matrix to a unit dual-quaternion
+///
+/// Fails if the matrix is not a rigid transform, within a tolerance of `1e-4`.
+pub fn from_matrix3x4<T: Float>(m: Matrix3x4<T>) -> Result<DualQuaternion<T>, MatrixError> {
+    from_rotation_matrix_and_translation(
+        [
+            [m[0][0], m[0][1], m[0][2]],
+            [m[1][0], m[1][1], m[1][2]],
+            [m[2][0], m[2][1], m[2][2]]
+        ],
+        [m[0][3], m[1][3], m[2][3]]
+    )
+}
+
+/// Returns the row-major rotation matrix of a unit quaternion
+fn rotation_matrix<T: Float>(q: Quaternion<T>) -> Matrix3<T> {
+    let one = T::one();
+    let two = T::from_f64(2.0);
+    let (w, [x, y, z]) = q;
+    [
+        [one - two * (y * y + z * z), two * (x * y - w * z), two * (x * z + w * y)],
+        [two * (x * y + w * z), one - two * (x * x + z * z), two * (y * z - w * x)],
+        [two * (x * z - w * y), two * (y * z + w * x), one - two * (x * x + y * y)]
+    ]
+}
+
+/// Validates a row-major rotation matrix and extracts its quaternion using Shepperd's method
+fn from_rotation_matrix_and_translation<T: Float>(
+    r: Matrix3<T>,
+    translation: Vector3<T>
+) -> Result<DualQuaternion<T>, MatrixError> {
+    let zero = T::zero();
+    let one = T::one();
+    let tolerance = matrix_tolerance();
+    for i in 0..3 {
+        for j in 0..3 {
+            let expected = if i == j { one } else { zero };
+            if !within(vecmath::vec3_dot(r[i], r[j]) - expected, tolerance) {
+                return Err(MatrixError::NotOrthonormal);
+            }
+        }
+    }
+    if vecmath::mat3_det(r) < zero {
+        return Err(MatrixError::Reflection);
+    }
+    if !translation.iter().all(|&x| is_finite(x)) {
+        return Err(MatrixError::NonFinite);
+    }
+
+    // pick the largest of the four candidate components to divide by,
+    // which keeps the square root argument well away from zero
+    let half = T::from_f64(0.5);
+    let quarter = T::from_f64(0.25);
+    let trace = r[0][0] + r[1][1] + r[2][2];
+    let rotation = if trace >= r[0][0] && trace >= r[1][1] && trace >= r[2][2] {
+        let w = half * math::sqrt(one + trace);
+        let s = quarter / w;
+        (w, [(r[2][1] - r[1][2]) * s, (r[0][2] - r[2][0]) * s, (r[1][0] - r[0][1]) * s])
+    } else if r[0][0] >= r[1][1] && r[0][0] >= r[2][2] {
+        let x = half * math::sqrt(one + r[0][0] - r[1][1] - r[2][2]);
+        let s = quarter / x;
+        ((r[2][1] - r[1][2]) * s, [x, (r[0][1] + r[1][0]) * s, (r[0][2] + r[2][0]) * s])
+    } else if r[1][1] >= r[2][2] {
+        let y = half * math::sqrt(one - r[0][0] + r[1][1] - r[2][2]);
+        let s = quarter / y;
+        ((r[0][2] - r[2][0]) * s, [(r[0][1] + r[1][0]) * s, y, (r[1][2] + r[2][1]) * s])
+    } else {
+        let z = half * math::sqrt(one - r[0][0] - r[1][1] + r[2][2]);
+        let s = quarter / z;
+        ((r[1][0] - r[0][1]) * s, [(r[0][2] + r[2][0]) * s, (r[1][2] + r[2][1]) * s, z])
+    };
+    let rotation = quaternion::scale(rotation, one / math::sqrt(quaternion::square_len(rotation)));
+    Ok(from_rotation_and_translation(rotation, translation))
+}
+
+/// Tolerance used when checking that a matrix is a rigid transform
+#[inline(always)]
+fn matrix_tolerance<T: Float>() -> T {
+    T::from_f64(1e-4)
+}
+
+/// Returns the absolute value of a scalar
+#[inline(always)]
+fn abs<T: Float>(x: T) -> T {
+    if x < T::zero() { -x } else { x }
+}
+
+/// Returns `true` if the magnitude of a scalar is at most `tolerance`, and `false` for NaN
+#[inline(always)]
+fn within<T: Float>(x: T, tolerance: T) -> bool {
+    abs(x) <= tolerance
+}
+
 /// Extracts rotation component from a dual-quaternion
 pub fn get_rotation<T: Float>(q: DualQuaternion<T>) -> Quaternion<T> {
     q.0
@@ -106,16 +698,80 @@ pub fn get_translation<T: Float>(q: DualQuaternion<T>) -> Vector3<T> {
     t.1
 }
 
+/// Transforms a point by a unit dual-quaternion, applying rotation then translation
+#[inline(always)]
+pub fn transform_point<T: Float>(q: DualQuaternion<T>, p: Vector3<T>) -> Vector3<T> {
+    vecmath::vec3_add(
+        quaternion::rotate_vector(q.0, p),
+        get_translation(q)
+    )
+}
+
+/// Transforms a direction vector by a unit dual-quaternion, applying rotation only
+#[inline(always)]
+pub fn transform_vector<T: Float>(q: DualQuaternion<T>, v: Vector3<T>) -> Vector3<T> {
+    quaternion::rotate_vector(q.0, v)
+}
+
+/// Transforms a slice of points by a unit dual-quaternion, writing the results to `dst`
+///
+/// Panics if `src` and `dst` have different lengths.
+pub fn transform_points<T: Float>(q: DualQuaternion<T>, src: &[Vector3<T>], dst: &mut [Vector3<T>]) {
+    assert_eq!(src.len(), dst.len());
+    let t = get_translation(q);
+    for (p, out) in src.iter().zip(dst.iter_mut()) {
+        *out = vecmath::vec3_add(quaternion::rotate_vector(q.0, *p), t);
+    }
+}
+
+/// Transforms a slice of direction vectors by a unit dual-quaternion, writing the results to `dst`
+///
+/// Panics if `src` and `dst` have different lengths.
+pub fn transform_vectors<T: Float>(q: DualQuaternion<T>, src: &[Vector3<T>], dst: &mut [Vector3<T>]) {
+    assert_eq!(src.len(), dst.len());
+    for (v, out) in src.iter().zip(dst.iter_mut()) {
+        *out = quaternion::rotate_vector(q.0, *v);
+    }
+}
+
+/// Composes the world transform of joint `j` from the world transforms of the joints before it
+///
+/// Panics if the parent of `j` doesn't come before it.
+#[inline(always)]
+fn joint_world_transform<T: Float>(
+    parents: &[Option<usize>],
+    locals: &[DualQuaternion<T>],
+    worlds: &[DualQuaternion<T>],
+    j: usize
+) -> DualQuaternion<T> {
+    match parents[j] {
+        Some(p) => {
+            assert!(p < j, "joint {} comes before its parent {}", j, p);
+            mul(worlds[p], locals[j])
+        }
+        None => locals[j],
+    }
+}
+
 /// Tests
 #[cfg(test)]
 mod test {
 
     use std::f32::consts::PI;
     use quaternion;
+    use vecmath;
     use vecmath::Vector3;
 
+    use test_util::pseudo_random;
+
     const EPSILON: f32 = 0.000001;
 
+    fn dq_approx_eq(a: super::DualQuaternion<f32>, b: super::DualQuaternion<f32>, epsilon: f32) -> bool {
+        ((a.0).0 - (b.0).0).abs() < epsilon &&
+        ((a.1).0 - (b.1).0).abs() < epsilon &&
+        (0..3).all(|i| ((a.0).1[i] - (b.0).1[i]).abs() < epsilon && ((a.1).1[i] - (b.1).1[i]).abs() < epsilon)
+    }
+
     #[test]
     fn test_construction_and_extraction() {
         let r = quaternion::euler_angles(PI, PI, PI);
@@ -223,4 +879,684 @@ mod test {
         assert!((r_prime.1[2] - 0.0).abs() < EPSILON);
     }
 
+    #[test]
+    fn test_transform_point_and_vector() {
+        let r = quaternion::euler_angles(0.3, -1.2, 2.5);
+        let t: Vector3<f32> = [1.0, 2.0, 3.0];
+        let dq = super::from_rotation_and_translation(r, t);
+
+        let p: Vector3<f32> = [-4.0, 0.5, 2.0];
+        let p_prime = super::transform_point(dq, p);
+        let p_expected = vecmath::vec3_add(
+            quaternion::rotate_vector(super::get_rotation(dq), p),
+            super::get_translation(dq)
+        );
+
+        assert!((p_prime[0] - p_expected[0]).abs() < EPSILON * 10.0);
+        assert!((p_prime[1] - p_expected[1]).abs() < EPSILON * 10.0);
+        assert!((p_prime[2] - p_expected[2]).abs() < EPSILON * 10.0);
+
+        let v_prime = super::transform_vector(dq, p);
+        let v_expected = quaternion::rotate_vector(r, p);
+
+        assert!((v_prime[0] - v_expected[0]).abs() < EPSILON);
+        assert!((v_prime[1] - v_expected[1]).abs() < EPSILON);
+        assert!((v_prime[2] - v_expected[2]).abs() < EPSILON);
+
+        // rotate 90 degrees about Z, then translate along X
+        let r = quaternion::euler_angles(0.0, 0.0, PI / 2.0);
+        let dq = super::from_rotation_and_translation(r, [1.0, 0.0, 0.0]);
+        let p_prime = super::transform_point(dq, [1.0, 0.0, 0.0]);
+
+        assert!((p_prime[0] - 1.0).abs() < EPSILON);
+        assert!((p_prime[1] - 1.0).abs() < EPSILON);
+        assert!((p_prime[2] - 0.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_transform_points_and_vectors() {
+        let r = quaternion::euler_angles(1.0, 0.5, -0.25);
+        let dq = super::from_rotation_and_translation(r, [0.0f32, -1.0, 5.0]);
+
+        let src: [Vector3<f32>; 3] = [[1.0, 0.0, 0.0], [0.0, 2.0, -1.0], [3.0, 3.0, 3.0]];
+        let mut points = [[0.0; 3]; 3];
+        let mut vectors = [[0.0; 3]; 3];
+        super::transform_points(dq, &src, &mut points);
+        super::transform_vectors(dq, &src, &mut vectors);
+
+        for i in 0..src.len() {
+            let p = super::transform_point(dq, src[i]);
+            let v = super::transform_vector(dq, src[i]);
+            for j in 0..3 {
+                assert!((points[i][j] - p[j]).abs() < EPSILON);
+                assert!((vectors[i][j] - v[j]).abs() < EPSILON);
+            }
+        }
+    }
+
+    #[test]
+    fn test_inverse() {
+        let r = quaternion::axis_angle(vecmath::vec3_normalized([0.4, 1.0, -1.0]), PI / 3.0);
+        let t = [1.0, -2.0, 3.0];
+        let dq = super::from_rotation_and_translation(r, t);
+        let dq_inv = super::inverse(dq);
+
+        // inverse undoes the transform on both sides
+        let p = [0.5, 4.0, -1.5];
+        let p_prime = super::transform_point(dq_inv, super::transform_point(dq, p));
+        let p_prime_2 = super::transform_point(dq, super::transform_point(dq_inv, p));
+        for i in 0..3 {
+            assert!((p_prime[i] - p[i]).abs() < EPSILON * 10.0);
+            assert!((p_prime_2[i] - p[i]).abs() < EPSILON * 10.0);
+        }
+
+        // inverse of a rigid transform is rotation by r^-1 and translation by -(r^-1 t)
+        let r_inv = quaternion::conj(r);
+        let t_inv = quaternion::rotate_vector(r_inv, vecmath::vec3_neg(t));
+        let r_prime = super::get_rotation(dq_inv);
+        let t_prime = super::get_translation(dq_inv);
+        assert!((r_prime.0 - r_inv.0).abs() < EPSILON);
+        for i in 0..3 {
+            assert!((r_prime.1[i] - r_inv.1[i]).abs() < EPSILON);
+            assert!((t_prime[i] - t_inv[i]).abs() < EPSILON * 10.0);
+        }
+    }
+
+    #[test]
+    fn test_try_inverse() {
+        let r = quaternion::axis_angle([1.0, 0.0, 0.0], PI / 4.0);
+        let dq = super::from_rotation_and_translation(r, [2.0, 0.0, -1.0]);
+
+        // non-unit input: q * q^-1 is still the identity
+        let scaled = super::scale(dq, 3.0);
+        let dq_prime = super::mul(scaled, super::try_inverse(scaled).unwrap());
+        let id = super::id::<f32>();
+        assert!(((dq_prime.0).0 - (id.0).0).abs() < EPSILON);
+        assert!(((dq_prime.1).0 - (id.1).0).abs() < EPSILON);
+        for i in 0..3 {
+            assert!(((dq_prime.0).1[i] - (id.0).1[i]).abs() < EPSILON);
+            assert!(((dq_prime.1).1[i] - (id.1).1[i]).abs() < EPSILON);
+        }
+
+        let zero = super::scale(dq, 0.0);
+        assert!(super::try_inverse(zero).is_none());
+    }
+
+    #[test]
+    fn test_between() {
+        let a = super::from_rotation_and_translation(
+            quaternion::axis_angle(vecmath::vec3_normalized([0.1, 0.2, 0.3]), 0.5), [1.0, 2.0, 3.0]
+        );
+        let b = super::from_rotation_and_translation(
+            quaternion::axis_angle(vecmath::vec3_normalized([-0.5, 2.0, 1.0]), PI / 2.0), [-3.0, 0.0, 4.0]
+        );
+
+        let b_prime = super::mul(a, super::between(a, b));
+        let r_prime = super::get_rotation(b_prime);
+        let t_prime = super::get_translation(b_prime);
+        let r_expected = super::get_rotation(b);
+        let t_expected = super::get_translation(b);
+
+        assert!((r_prime.0 - r_expected.0).abs() < EPSILON);
+        for i in 0..3 {
+            assert!((r_prime.1[i] - r_expected.1[i]).abs() < EPSILON);
+            assert!((t_prime[i] - t_expected[i]).abs() < EPSILON * 10.0);
+        }
+    }
+
+    #[test]
+    fn test_sclerp_endpoints() {
+        let a = super::from_rotation_and_translation(
+            quaternion::axis_angle(vecmath::vec3_normalized([1.0, 1.0, 0.0]), 0.7), [1.0, 2.0, 3.0]
+        );
+        let b = super::from_rotation_and_translation(
+            quaternion::axis_angle(vecmath::vec3_normalized([0.0, -1.0, 2.0]), 2.5), [-2.0, 0.5, 1.0]
+        );
+
+        assert!(dq_approx_eq(super::sclerp(a, b, 0.0), a, EPSILON * 10.0));
+        assert!(dq_approx_eq(super::sclerp(a, b, 1.0), b, EPSILON * 10.0));
+
+        // the antipodal representation of b gives the same path
+        let b_neg = super::scale(b, -1.0);
+        for &t in &[0.25, 0.5, 0.75] {
+            assert!(dq_approx_eq(super::sclerp(a, b, t), super::sclerp(a, b_neg, t), EPSILON * 10.0));
+        }
+    }
+
+    #[test]
+    fn test_sclerp_screw_motion() {
+        // quarter turn about Z combined with a translation along Z
+        let a = super::id();
+        let b = super::from_rotation_and_translation(
+            quaternion::axis_angle([0.0, 0.0, 1.0], PI / 2.0), [0.0, 0.0, 2.0]
+        );
+
+        let mid = super::sclerp(a, b, 0.5);
+        let r_expected = quaternion::axis_angle([0.0, 0.0, 1.0], PI / 4.0);
+        let expected = super::from_rotation_and_translation(r_expected, [0.0, 0.0, 1.0]);
+        assert!(dq_approx_eq(mid, expected, EPSILON * 10.0));
+
+        // constant screw motion: both halves are the same relative transform
+        let c = super::from_rotation_and_translation(
+            quaternion::axis_angle(vecmath::vec3_normalized([1.0, -2.0, 0.5]), 1.3), [4.0, -1.0, 2.0]
+        );
+        let mid = super::sclerp(b, c, 0.5);
+        assert!(dq_approx_eq(super::between(b, mid), super::between(mid, c), EPSILON * 10.0));
+    }
+
+    #[test]
+    fn test_sclerp_degenerate() {
+        // pure translation
+        let a = super::from_rotation_and_translation(quaternion::id(), [1.0f32, 0.0, 0.0]);
+        let b = super::from_rotation_and_translation(quaternion::id(), [3.0, 4.0, 0.0]);
+        let mid = super::sclerp(a, b, 0.5);
+        let t = super::get_translation(mid);
+        assert!((t[0] - 2.0).abs() < EPSILON);
+        assert!((t[1] - 2.0).abs() < EPSILON);
+        assert!((t[2] - 0.0).abs() < EPSILON);
+
+        // identical poses
+        let c = super::from_rotation_and_translation(quaternion::axis_angle([0.0, 1.0, 0.0], 1.0), [1.0, 2.0, 3.0]);
+        let mid = super::sclerp(c, c, 0.3);
+        assert!(dq_approx_eq(mid, c, EPSILON));
+        assert!(((mid.0).0).is_finite() && ((mid.1).0).is_finite());
+    }
+
+    #[test]
+    fn test_blend_rigid() {
+        let q1 = super::from_rotation_and_translation(
+            quaternion::axis_angle(vecmath::vec3_normalized([1.0, 2.0, 3.0]), 0.4), [1.0f32, 0.0, 0.0]
+        );
+        let q2 = super::from_rotation_and_translation(
+            quaternion::axis_angle(vecmath::vec3_normalized([-1.0, 0.5, 0.0]), 2.0), [0.0, 3.0, -1.0]
+        );
+        let q3 = super::scale(super::from_rotation_and_translation(
+            quaternion::axis_angle([0.0, 0.0, 1.0], 1.0), [2.0, 2.0, 2.0]
+        ), -1.0);
+
+        let dq = super::blend(&[(q1, 0.5), (q2, 0.3), (q3, 0.2)]);
+
+        // unit real part, dual part orthogonal to it
+        assert!((super::dot(dq, dq) - 1.0).abs() < EPSILON * 10.0);
+        assert!(quaternion::dot(dq.0, dq.1).abs() < EPSILON * 10.0);
+
+        // distances between points are preserved
+        let a = super::transform_point(dq, [1.0, 2.0, 3.0]);
+        let b = super::transform_point(dq, [-1.0, 0.0, 2.0]);
+        let d = vecmath::vec3_len(vecmath::vec3_sub(a, b));
+        assert!((d - 3.0).abs() < EPSILON * 10.0);
+
+        // fixed arity fast path agrees with the slice version
+        let q4 = super::id();
+        let dq = super::blend(&[(q1, 0.4), (q2, 0.3), (q3, 0.2), (q4, 0.1)]);
+        let dq4 = super::blend4([q1, q2, q3, q4], [0.4, 0.3, 0.2, 0.1]);
+        assert!(dq_approx_eq(dq, dq4, EPSILON));
+
+        // a single influence is returned unchanged
+        assert!(dq_approx_eq(super::blend(&[(q2, 1.0)]), q2, EPSILON));
+        assert!(dq_approx_eq(super::blend(&[]), super::id(), EPSILON));
+    }
+
+    #[test]
+    fn test_blend_zero_weights() {
+        let q = super::from_rotation_and_translation(quaternion::axis_angle([0.0, 0.0, 1.0], 1.0), [1.0f32, 2.0, 3.0]);
+        assert_eq!(super::blend(&[(q, 0.0), (q, 0.0)]), super::id());
+        assert_eq!(super::blend4([q; 4], [0.0; 4]), super::id());
+        // opposite weights cancel the real part as well
+        assert_eq!(super::blend(&[(q, 1.0), (q, -1.0)]), super::id());
+    }
+
+    #[test]
+    fn test_blend_no_candy_wrapper() {
+        // half way between no twist and a 180 degree twist about X
+        let q1 = super::id();
+        let q2 = super::from_rotation_and_translation(quaternion::axis_angle([1.0, 0.0, 0.0], PI), [0.0, 0.0, 0.0]);
+        let dq = super::blend(&[(q1, 0.5), (q2, 0.5)]);
+
+        // a point away from the twist axis keeps its distance instead of collapsing onto it
+        let p = super::transform_point(dq, [0.0, 1.0, 0.0]);
+        // (exactly 180 degrees is ambiguous, so either direction of the quarter turn is fine)
+        assert!((p[0] - 0.0).abs() < EPSILON);
+        assert!((p[1] - 0.0).abs() < EPSILON);
+        assert!((p[2].abs() - 1.0).abs() < EPSILON);
+
+        // just past 180 degrees the antipodal representation is flipped back into the pivot's hemisphere
+        let q3 = super::from_rotation_and_translation(quaternion::axis_angle([1.0, 0.0, 0.0], PI * 1.2), [0.0, 0.0, 0.0]);
+        let dq = super::blend(&[(q1, 0.5), (q3, 0.5)]);
+        let p = super::transform_point(dq, [0.0, 1.0, 0.0]);
+        assert!((vecmath::vec3_len(p) - 1.0).abs() < EPSILON);
+        assert!((p[1] - (PI * 0.4).cos()).abs() < EPSILON);
+        assert!((p[2] + (PI * 0.4).sin()).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_screw_round_trip() {
+        let dq = super::from_rotation_and_translation(
+            quaternion::axis_angle(vecmath::vec3_normalized([1.0, -1.0, 2.0]), 2.0), [3.0f32, 1.0, -2.0]
+        );
+        let screw = super::to_screw(dq);
+        assert!((vecmath::vec3_len(screw.axis) - 1.0).abs() < EPSILON);
+        assert!((screw.angle - 2.0).abs() < EPSILON);
+        assert!(dq_approx_eq(super::from_screw(screw), dq, EPSILON * 10.0));
+
+        // any point on the axis only moves along the axis
+        let p = screw.point();
+        let p_prime = super::transform_point(dq, p);
+        let offset = vecmath::vec3_sub(p_prime, p);
+        let along = vecmath::vec3_scale(screw.axis, screw.translation);
+        for i in 0..3 {
+            assert!((offset[i] - along[i]).abs() < EPSILON * 10.0);
+        }
+    }
+
+    #[test]
+    fn test_screw_known_axis() {
+        // half turn about the Z axis through (1, 0, 0), moving 2 units along Z
+        let r = quaternion::axis_angle([0.0, 0.0, 1.0], PI);
+        let p = [1.0, 0.0, 0.0];
+        let t = vecmath::vec3_add(vecmath::vec3_sub(p, quaternion::rotate_vector(r, p)), [0.0, 0.0, 2.0]);
+        let screw = super::to_screw(super::from_rotation_and_translation(r, t));
+
+        assert!((screw.angle - PI).abs() < EPSILON);
+        assert!((screw.translation - 2.0).abs() < EPSILON);
+        assert!((screw.pitch() - 2.0 / PI).abs() < EPSILON);
+        assert_eq!(screw.dual_angle(), super::Dual::new(screw.angle, screw.translation));
+        let expected = [[0.0, 0.0, 1.0], [0.0, -1.0, 0.0], [1.0, 0.0, 0.0]];
+        let point = screw.point();
+        for i in 0..3 {
+            assert!((screw.axis[i] - expected[0][i]).abs() < EPSILON);
+            assert!((screw.moment[i] - expected[1][i]).abs() < EPSILON * 10.0);
+            assert!((point[i] - expected[2][i]).abs() < EPSILON * 10.0);
+        }
+    }
+
+    #[test]
+    fn test_screw_pure_translation() {
+        let dq = super::from_rotation_and_translation(quaternion::id(), [0.0f32, 3.0, 4.0]);
+        let screw = super::to_screw(dq);
+        assert_eq!(screw.angle, 0.0);
+        assert_eq!(screw.moment, [0.0, 0.0, 0.0]);
+        assert!((screw.translation - 5.0).abs() < EPSILON);
+        assert!((screw.axis[1] - 0.6).abs() < EPSILON);
+        assert!((screw.axis[2] - 0.8).abs() < EPSILON);
+        assert!(dq_approx_eq(super::from_screw(screw), dq, EPSILON));
+
+        let screw = super::to_screw(super::id::<f32>());
+        assert_eq!(screw.translation, 0.0);
+        assert_eq!(screw.axis, [0.0, 0.0, 1.0]);
+        assert!(screw.pitch().is_nan());
+        assert!(dq_approx_eq(super::from_screw(screw), super::id(), EPSILON));
+    }
+
+    #[test]
+    fn test_screw_small_rotation() {
+        // a tiny rotation keeps its screw axis, far from the origin, instead of becoming a translation
+        let dq = super::from_rotation_and_translation(quaternion::axis_angle([0.0, 0.0, 1.0], 1.5e-6), [1.0f64, 0.0, 0.0]);
+        let screw = super::to_screw(dq);
+        assert!((screw.angle - 1.5e-6).abs() < 1e-15);
+        assert_eq!(screw.axis, [0.0, 0.0, 1.0]);
+        let dq_prime = super::from_screw(screw);
+        assert!(((dq_prime.0).1[2] - (dq.0).1[2]).abs() < 1e-15);
+        let (t, t_prime) = (super::get_translation(dq), super::get_translation(dq_prime));
+        for i in 0..3 {
+            assert!((t_prime[i] - t[i]).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_exp_log_round_trip() {
+        let mut state = 1;
+        for _ in 0..1000 {
+            let axis = vecmath::vec3_normalized([
+                pseudo_random::<f64>(&mut state), pseudo_random(&mut state), pseudo_random(&mut state)
+            ]);
+            // angles past π and the negated forms have a negative real scalar
+            let angle = (pseudo_random::<f64>(&mut state) + 1.0) * ::std::f64::consts::PI;
+            let t = vecmath::vec3_scale([
+                pseudo_random(&mut state), pseudo_random(&mut state), pseudo_random(&mut state)
+            ], 10.0);
+            let pose = super::from_rotation_and_translation(quaternion::axis_angle(axis, angle), t);
+            for &dq in &[pose, super::scale(pose, -1.0)] {
+                let dq_prime = super::exp(super::log(dq));
+                // the same rigid transform, with a non-negative real scalar
+                let dq = if (dq.0).0 < 0.0 { super::scale(dq, -1.0) } else { dq };
+                assert!(((dq_prime.0).0 - (dq.0).0).abs() < 1e-12);
+                assert!(((dq_prime.1).0 - (dq.1).0).abs() < 1e-12);
+                for i in 0..3 {
+                    assert!(((dq_prime.0).1[i] - (dq.0).1[i]).abs() < 1e-12);
+                    assert!(((dq_prime.1).1[i] - (dq.1).1[i]).abs() < 1e-12);
+                }
+            }
+        }
+
+        // a pure translation with a negative real scalar
+        let dq = super::scale(super::from_rotation_and_translation(quaternion::id(), [1.0, 2.0, 3.0]), -1.0);
+        assert_eq!(super::log(dq), ([0.0, 0.0, 0.0], [1.0, 2.0, 3.0]));
+
+        // a full turn in f32
+        let dq = super::from_rotation_and_translation(quaternion::axis_angle([0.0, 0.0, 1.0], 2.0 * PI), [1.0, 0.0, 0.0]);
+        assert!(dq_approx_eq(super::exp(super::log(dq)), super::scale(dq, -1.0), EPSILON));
+    }
+
+    #[test]
+    fn test_exp_log_known_values() {
+        // a pure translation twist
+        let dq = super::exp(([0.0, 0.0, 0.0], [1.0f32, 2.0, 3.0]));
+        assert!(dq_approx_eq(dq, super::from_rotation_and_translation(quaternion::id(), [1.0, 2.0, 3.0]), EPSILON));
+
+        // a pure rotation twist
+        let dq = super::exp(([0.0, 0.0, PI / 2.0], [0.0, 0.0, 0.0]));
+        let r = quaternion::axis_angle([0.0, 0.0, 1.0], PI / 2.0);
+        assert!(dq_approx_eq(dq, super::from_rotation_and_translation(r, [0.0, 0.0, 0.0]), EPSILON));
+
+        // screw motion: the log is the axis scaled by the angle and the translation along the axis
+        let dq = super::from_rotation_and_translation(r, [0.0, 0.0, 3.0]);
+        let twist = super::log(dq);
+        let expected: super::Twist<f32> = ([0.0, 0.0, PI / 2.0], [0.0, 0.0, 3.0]);
+        for i in 0..3 {
+            assert!((twist.0[i] - expected.0[i]).abs() < EPSILON);
+            assert!((twist.1[i] - expected.1[i]).abs() < EPSILON);
+        }
+
+        // the identity maps to the zero twist and back
+        let twist = super::log(super::id::<f32>());
+        assert_eq!(twist, ([0.0, 0.0, 0.0], [0.0, 0.0, 0.0]));
+        assert!(dq_approx_eq(super::exp(twist), super::id(), EPSILON));
+    }
+
+    #[test]
+    fn test_exp_log_near_zero_rotation() {
+        // both sides of the Taylor series threshold round trip
+        for &angle in &[0.0, 1e-9, 1e-5, 0.01414, 0.01415, 0.05] {
+            let twist = ([angle, 0.0, -angle], [1.0, 2.0, -1.0]);
+            let dq: super::DualQuaternion<f64> = super::exp(twist);
+            assert!((super::dot(dq, dq) - 1.0).abs() < 1e-12);
+            assert!(quaternion::dot(dq.0, dq.1).abs() < 1e-12);
+
+            let twist_prime = super::log(dq);
+            for i in 0..3 {
+                assert!((twist_prime.0[i] - twist.0[i]).abs() < 1e-12);
+                assert!((twist_prime.1[i] - twist.1[i]).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pow_integer_exponents() {
+        let dq = super::from_rotation_and_translation(
+            quaternion::axis_angle(vecmath::vec3_normalized([2.0, 1.0, -1.0]), 0.8), [1.0, -0.5, 2.0]
+        );
+        let mut expected: super::DualQuaternion<f64> = super::id();
+        for n in 0..6 {
+            let dq_prime = super::pow(dq, n as f64);
+            assert!(((dq_prime.0).0 - (expected.0).0).abs() < 1e-12);
+            assert!(((dq_prime.1).0 - (expected.1).0).abs() < 1e-12);
+            for i in 0..3 {
+                assert!(((dq_prime.0).1[i] - (expected.0).1[i]).abs() < 1e-12);
+                assert!(((dq_prime.1).1[i] - (expected.1).1[i]).abs() < 1e-12);
+            }
+            expected = super::mul(expected, dq);
+        }
+
+        let dq_inv = super::pow(dq, -1.0);
+        let inv = super::inverse(dq);
+        assert!(((dq_inv.0).0 - (inv.0).0).abs() < 1e-12);
+        assert!(((dq_inv.1).0 - (inv.1).0).abs() < 1e-12);
+        for i in 0..3 {
+            assert!(((dq_inv.0).1[i] - (inv.0).1[i]).abs() < 1e-12);
+            assert!(((dq_inv.1).1[i] - (inv.1).1[i]).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_sqrt() {
+        let dq = super::from_rotation_and_translation(
+            quaternion::axis_angle([0.0, 1.0, 0.0], PI / 2.0), [3.0, 0.0, 1.0]
+        );
+        let half = super::sqrt(dq);
+        assert!(dq_approx_eq(super::mul(half, half), dq, EPSILON * 10.0));
+
+        // half way along the screw motion
+        assert!(dq_approx_eq(half, super::sclerp(super::id(), dq, 0.5), EPSILON * 10.0));
+        let r = super::get_rotation(half);
+        let expected = quaternion::axis_angle([0.0, 1.0, 0.0], PI / 4.0);
+        assert!((r.0 - expected.0).abs() < EPSILON);
+        assert!((r.1[1] - expected.1[1]).abs() < EPSILON);
+
+        // the negated form is the same transform and has the same square root
+        let half_neg = super::sqrt(super::scale(dq, -1.0));
+        assert!(dq_approx_eq(half_neg, half, EPSILON * 10.0));
+    }
+
+    #[test]
+    fn test_pow_negative_real_scalar() {
+        let t = super::from_rotation_and_translation(quaternion::id(), [1.0f32, 2.0, 3.0]);
+        let half = super::pow(super::scale(t, -1.0), 0.5);
+        assert!(dq_approx_eq(half, super::from_rotation_and_translation(quaternion::id(), [0.5, 1.0, 1.5]), EPSILON));
+
+        // a rotation past π is raised along the shorter way around
+        let dq = super::from_rotation_and_translation(quaternion::axis_angle([0.0, 0.0, 1.0], 1.5 * PI), [0.0, 0.0, 2.0]);
+        assert!((dq.0).0 < 0.0);
+        let third = super::pow(dq, 1.0 / 3.0);
+        let expected = super::from_rotation_and_translation(quaternion::axis_angle([0.0, 0.0, 1.0], -PI / 6.0), [0.0, 0.0, 2.0 / 3.0]);
+        assert!(dq_approx_eq(third, expected, EPSILON * 10.0));
+        let cube = super::mul(super::mul(third, third), third);
+        assert!(dq_approx_eq(cube, super::scale(dq, -1.0), EPSILON * 10.0));
+    }
+
+    #[test]
+    fn test_matrix_round_trip() {
+        let dq = super::from_rotation_and_translation(
+            quaternion::axis_angle(vecmath::vec3_normalized([1.0, 3.0, -2.0]), 2.2), [4.0f32, -1.0, 0.5]
+        );
+        let m4 = super::to_matrix4(dq);
+        let m3x4 = super::to_matrix3x4(dq);
+
+        // both matrices transform points like the dual-quaternion does
+        let p = [1.0, -2.0, 0.5];
+        let expected = super::transform_point(dq, p);
+        let p4 = vecmath::col_mat4_transform(m4, [p[0], p[1], p[2], 1.0]);
+        let p3x4 = vecmath::row_mat3x4_transform_pos3(m3x4, p);
+        for i in 0..3 {
+            assert!((p4[i] - expected[i]).abs() < EPSILON * 10.0);
+            assert!((p3x4[i] - expected[i]).abs() < EPSILON * 10.0);
+        }
+
+        assert!(dq_approx_eq(super::from_matrix4(m4).unwrap(), dq, EPSILON * 10.0));
+        assert!(dq_approx_eq(super::from_matrix3x4(m3x4).unwrap(), dq, EPSILON * 10.0));
+    }
+
+    #[test]
+    fn test_matrix_shepperd_branches() {
+        // rotations near 180 degrees about each axis exercise every branch
+        let axes: [Vector3<f32>; 4] = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0], [1.0, 1.0, 1.0]];
+        for &axis in &axes {
+            for &angle in &[0.1, PI * 0.99, PI] {
+                let r = quaternion::axis_angle(vecmath::vec3_normalized(axis), angle);
+                let dq = super::from_rotation_and_translation(r, [1.0, 2.0, 3.0]);
+                let dq_prime = super::from_matrix3x4(super::to_matrix3x4(dq)).unwrap();
+
+                // either sign of the quaternion represents the same rotation
+                let dq_prime = if super::dot(dq, dq_prime) < 0.0 { super::scale(dq_prime, -1.0) } else { dq_prime };
+                assert!(dq_approx_eq(dq_prime, dq, EPSILON * 10.0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_errors() {
+        let m = super::to_matrix3x4(super::id::<f32>());
+
+        let mut scaled = m;
+        scaled[0][0] = 2.0;
+        assert_eq!(super::from_matrix3x4(scaled), Err(super::MatrixError::NotOrthonormal));
+
+        let mut mirrored = m;
+        mirrored[2][2] = -1.0;
+        assert_eq!(super::from_matrix3x4(mirrored), Err(super::MatrixError::Reflection));
+
+        let mut projective = super::to_matrix4(super::id::<f32>());
+        projective[2][3] = 1.0;
+        assert_eq!(super::from_matrix4(projective), Err(super::MatrixError::NotAffine));
+
+        let mut nan = m;
+        nan[0][0] = f32::NAN;
+        assert_eq!(super::from_matrix3x4(nan), Err(super::MatrixError::NotOrthonormal));
+        let mut nan = m;
+        nan[1][3] = f32::NAN;
+        assert_eq!(super::from_matrix3x4(nan), Err(super::MatrixError::NonFinite));
+        let mut nan = super::to_matrix4(super::id::<f32>());
+        nan[3][3] = f32::NAN;
+        assert_eq!(super::from_matrix4(nan), Err(super::MatrixError::NotAffine));
+
+        // small numerical drift is tolerated
+        let mut drifted = m;
+        drifted[1][1] = 1.00001;
+        assert!(super::from_matrix3x4(drifted).is_ok());
+    }
+
+    #[test]
+    fn test_normalize_exact() {
+        let dq = super::from_rotation_and_translation(
+            quaternion::axis_angle(vecmath::vec3_normalized([1.0, 2.0, 2.0]), 1.0), [3.0f64, -1.0, 2.0]
+        );
+
+        // scaled and skewed inputs are projected back onto the same rigid transform
+        let mut skewed = super::scale(dq, 7.5);
+        (skewed.1).0 += 0.5;
+        (skewed.1).1[1] -= 0.25;
+        let n = super::normalize(skewed);
+        assert!((super::dot(n, n) - 1.0).abs() < 1e-12);
+        assert!(quaternion::dot(n.0, n.1).abs() < 1e-12);
+        let n = super::normalize(super::scale(dq, 7.5));
+        assert!(((n.0).0 - (dq.0).0).abs() < 1e-12);
+        assert!(((n.1).0 - (dq.1).0).abs() < 1e-12);
+        for i in 0..3 {
+            assert!(((n.0).1[i] - (dq.0).1[i]).abs() < 1e-12);
+            assert!(((n.1).1[i] - (dq.1).1[i]).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_try_normalize() {
+        let dq = super::scale(super::from_rotation_and_translation(quaternion::id(), [1.0f32, 2.0, 3.0]), 2.0);
+        assert!(dq_approx_eq(super::try_normalize(dq).unwrap(), super::normalize(dq), EPSILON));
+
+        let zero = super::scale(dq, 0.0);
+        assert_eq!(super::try_normalize(zero), Err(super::NormalizeError::Zero));
+
+        let mut nan = dq;
+        (nan.0).1[2] = f32::NAN;
+        assert_eq!(super::try_normalize(nan), Err(super::NormalizeError::NonFinite));
+
+        let mut inf = dq;
+        (inf.1).0 = f32::INFINITY;
+        assert_eq!(super::try_normalize(inf), Err(super::NormalizeError::NonFinite));
+
+        // finite components whose squares overflow
+        let huge = super::scale(dq, 1e20);
+        assert!(dq_approx_eq(super::try_normalize(huge).unwrap(), super::normalize(dq), EPSILON));
+        let tiny = super::scale(dq, 1e-30);
+        assert!(dq_approx_eq(super::try_normalize(tiny).unwrap(), super::normalize(dq), EPSILON));
+
+        let mut lopsided = super::scale(dq, 1e-30);
+        (lopsided.1).1[0] = 1e30;
+        assert_eq!(super::try_normalize(lopsided), Err(super::NormalizeError::Overflow));
+    }
+
+    #[test]
+    fn test_normalize_drift() {
+        let step = super::from_rotation_and_translation(
+            quaternion::axis_angle(vecmath::vec3_normalized([0.3f32, -1.0, 0.5]), 0.001), [0.001, 0.0, -0.002]
+        );
+
+        let mut renormalized = super::id();
+        let mut normalized = super::id();
+        for _ in 0..1000000 {
+            renormalized = super::mul(renormalized, step);
+            super::renormalize_in_place(&mut renormalized);
+            normalized = super::normalize(super::mul(normalized, step));
+        }
+
+        for &q in &[renormalized, normalized] {
+            assert!((super::dot(q, q) - 1.0).abs() < 1e-5);
+            assert!(quaternion::dot(q.0, q.1).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_conjugates() {
+        let dq = super::from_rotation_and_translation(
+            quaternion::axis_angle(vecmath::vec3_normalized([1.0, -1.0, 0.5]), 0.9), [2.0f32, 1.0, -3.0]
+        );
+
+        // the quaternion conjugate is the inverse
+        assert!(dq_approx_eq(super::mul(dq, super::conj(dq)), super::id(), EPSILON * 10.0));
+
+        // the combined conjugate transforms points
+        let p = [0.5, -2.0, 1.0];
+        let p_dq = (quaternion::id(), (0.0, p));
+        let p_prime = super::mul(super::mul(dq, p_dq), super::combined_conj(dq));
+        let expected = super::transform_point(dq, p);
+        assert!(((p_prime.0).0 - 1.0).abs() < EPSILON);
+        for (i, e) in expected.iter().enumerate() {
+            assert!(((p_prime.1).1[i] - e).abs() < EPSILON * 10.0);
+        }
+
+        // conjugates of conjugates
+        assert_eq!(super::dual_conj(super::dual_conj(dq)), dq);
+        assert_eq!(super::combined_conj(dq), super::conj(super::dual_conj(dq)));
+    }
+
+    #[test]
+    fn test_norm() {
+        let dq = super::from_rotation_and_translation(
+            quaternion::axis_angle([0.0, 1.0, 0.0], 0.3), [1.0f32, 2.0, 3.0]
+        );
+        let n = super::norm(dq);
+        assert!((n.real - 1.0).abs() < EPSILON);
+        assert!(n.dual.abs() < EPSILON);
+
+        // scaling by a dual number scales the norm by it
+        let scaled = super::add(super::scale(dq, 2.0), (quaternion::scale(dq.1, 0.0), quaternion::scale(dq.0, 0.5)));
+        let n = super::norm(scaled);
+        assert!((n.real - 2.0).abs() < EPSILON);
+        assert!((n.dual - 0.5).abs() < EPSILON);
+    }
+
+    #[cfg(feature = "deterministic")]
+    #[test]
+    fn test_deterministic_golden_values() {
+        fn bits(q: super::DualQuaternion<f64>) -> [u64; 8] {
+            let ((a, [b, c, d]), (e, [f, g, h])) = q;
+            [a, b, c, d, e, f, g, h].map(f64::to_bits)
+        }
+
+        // inputs are exact literals, so no platform math is involved in constructing them
+        let a = super::normalize(((1.0f64, [2.0, -3.0, 0.5]), (0.25, [-1.0, 0.5, 2.0])));
+        let b = super::from_rotation_and_translation((0.5f64, [0.5, 0.5, -0.5]), [4.0, -1.0, 2.5]);
+        assert_eq!(bits(a), [
+            0x3fd0f43a45cdedad, 0x3fe0f43a45cdedad, 0xbfe96e5768b4e484, 0x3fc0f43a45cdedad,
+            0x3fbba96c8cd6b9b0, 0xbfc7334244930f58, 0x3f7c8ddb68177520, 0x3fe19f8d6a3e7a6d
+        ]);
+        assert_eq!(bits(super::sclerp(a, b, 0.3)), [
+            0x3f88da775add00a0, 0x3fcf1582db59e67c, 0xbfed24718840a26e, 0x3fd55f10d16a28d0,
+            0x3fc7e0d0881dc1b3, 0xbfdd5f2ed2f6b1d0, 0xbfd2d9ce294bc698, 0xbfde7bf6dae33a8e
+        ]);
+
+        let screw = super::to_screw(a);
+        assert_eq!(screw.angle.to_bits(), 0x4004d7d0d6fe2855);
+        assert_eq!(screw.translation.to_bits(), 0xbfccafc99017a898);
+
+        let (w, v) = super::log(a);
+        assert_eq!(w.map(f64::to_bits), [0x3ff6e772005aeb01, 0xc0012d9580443041, 0x3fd6e772005aeb01]);
+        assert_eq!(v.map(f64::to_bits), [0xbfe233923b33454c, 0x3fc197a561c7f3be, 0x3ff77dedf8ffdfb8]);
+
+        let a = super::normalize(((1.0f32, [2.0, -3.0, 0.5]), (0.25, [-1.0, 0.5, 2.0])));
+        let b = super::from_rotation_and_translation((0.5f32, [0.5, 0.5, -0.5]), [4.0, -1.0, 2.5]);
+        let ((w, r), (x, d)) = super::sclerp(a, b, 0.3);
+        assert_eq!((w.to_bits(), r.map(f32::to_bits)), (0x3c46d3c0, [0x3e78ac18, 0xbf69238c, 0x3eaaf886]));
+        assert_eq!((x.to_bits(), d.map(f32::to_bits)), (0x3e3f0684, [0xbeeaf976, 0xbe96ce70, 0xbef3dfb6]));
+    }
+
 }
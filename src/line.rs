@@ -0,0 +1,188 @@
+//! Lines in Plücker coordinates and their transformation by dual-quaternions
+
+use vecmath::{self, Vector3};
+use vecmath::traits::Float;
+
+use math;
+use super::{abs, conj, mul, DualQuaternion, Screw};
+
+/// A line in Plücker coordinates, with a unit direction and a moment about the origin
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Line<T> {
+    /// Unit direction of the line
+    pub direction: Vector3<T>,
+    /// Moment of the line, `p x direction` for any point `p` on the line
+    pub moment: Vector3<T>,
+}
+
+impl<T: Float> Line<T> {
+    /// Constructs a line through a point along a direction, which doesn't need to be normalized
+    pub fn from_point_and_direction(point: Vector3<T>, direction: Vector3<T>) -> Line<T> {
+        let direction = math::vec3_normalized(direction);
+        Line {
+            direction,
+            moment: vecmath::vec3_cross(point, direction),
+        }
+    }
+
+    /// Constructs a line through two distinct points, directed from `a` to `b`
+    pub fn from_points(a: Vector3<T>, b: Vector3<T>) -> Line<T> {
+        Line::from_point_and_direction(a, vecmath::vec3_sub(b, a))
+    }
+
+    /// Returns the point on the line closest to the origin
+    pub fn point(&self) -> Vector3<T> {
+        vecmath::vec3_cross(self.direction, self.moment)
+    }
+
+    /// Returns the distance from a point to the line
+    pub fn distance_to_point(&self, p: Vector3<T>) -> T {
+        math::vec3_len(vecmath::vec3_sub(vecmath::vec3_cross(p, self.direction), self.moment))
+    }
+}
+
+impl<T: Float> From<Screw<T>> for Line<T> {
+    /// Returns the axis of a screw motion
+    fn from(screw: Screw<T>) -> Line<T> {
+        Line {
+            direction: screw.axis,
+            moment: screw.moment,
+        }
+    }
+}
+
+/// Transforms a line by a unit dual-quaternion
+pub fn transform_line<T: Float>(q: DualQuaternion<T>, line: Line<T>) -> Line<T> {
+    let zero = T::zero();
+    let l = mul(mul(q, ((zero, line.direction), (zero, line.moment))), conj(q));
+    Line {
+        direction: (l.0).1,
+        moment: (l.1).1,
+    }
+}
+
+/// Returns the shortest distance between two lines
+pub fn distance<T: Float>(a: Line<T>, b: Line<T>) -> T {
+    let n = vecmath::vec3_cross(a.direction, b.direction);
+    let n_len = math::vec3_len(n);
+    if n_len < parallel_epsilon() {
+        // parallel lines, compare moments of lines with matching orientation
+        let s = if vecmath::vec3_dot(a.direction, b.direction) < T::zero() { -T::one() } else { T::one() };
+        let dm = vecmath::vec3_sub(a.moment, vecmath::vec3_scale(b.moment, s));
+        return math::vec3_len(vecmath::vec3_cross(a.direction, dm));
+    }
+    abs(vecmath::vec3_dot(a.direction, b.moment) + vecmath::vec3_dot(b.direction, a.moment)) / n_len
+}
+
+/// Returns the closest points on two lines, first on `a` and then on `b`,
+/// or `None` if the lines are parallel
+pub fn closest_points<T: Float>(a: Line<T>, b: Line<T>) -> Option<(Vector3<T>, Vector3<T>)> {
+    let n = vecmath::vec3_cross(a.direction, b.direction);
+    let n_len_sq = vecmath::vec3_square_len(n);
+    if n_len_sq < parallel_epsilon::<T>() * parallel_epsilon::<T>() {
+        return None;
+    }
+    let pa = a.point();
+    let pb = b.point();
+    let d = vecmath::vec3_sub(pb, pa);
+    let s = vecmath::vec3_dot(vecmath::vec3_cross(d, b.direction), n) / n_len_sq;
+    let t = vecmath::vec3_dot(vecmath::vec3_cross(d, a.direction), n) / n_len_sq;
+    Some((
+        vecmath::vec3_add(pa, vecmath::vec3_scale(a.direction, s)),
+        vecmath::vec3_add(pb, vecmath::vec3_scale(b.direction, t))
+    ))
+}
+
+/// Returns the intersection point of two lines, or `None` if they are parallel
+/// or pass each other at a distance greater than `tolerance`
+pub fn intersection<T: Float>(a: Line<T>, b: Line<T>, tolerance: T) -> Option<Vector3<T>> {
+    let (pa, pb) = closest_points(a, b)?;
+    if math::vec3_len(vecmath::vec3_sub(pa, pb)) > tolerance {
+        return None;
+    }
+    Some(vecmath::vec3_scale(vecmath::vec3_add(pa, pb), T::from_f64(0.5)))
+}
+
+/// Returns the intersection point of the axes of two screw motions, see `intersection`
+pub fn intersect_screw_axes<T: Float>(a: Screw<T>, b: Screw<T>, tolerance: T) -> Option<Vector3<T>> {
+    intersection(a.into(), b.into(), tolerance)
+}
+
+/// Length of the cross product of two unit directions below which they are considered parallel
+#[inline(always)]
+fn parallel_epsilon<T: Float>() -> T {
+    T::from_f64(1e-6)
+}
+
+/// Tests
+#[cfg(test)]
+mod test {
+
+    use std::f64::consts::PI;
+    use quaternion;
+    use vecmath;
+    use vecmath::Vector3;
+
+    use super::Line;
+    use super::super::{from_rotation_and_translation, to_screw, transform_point};
+
+    const EPSILON: f64 = 0.000000001;
+
+    #[test]
+    fn test_transform_line() {
+        let q = from_rotation_and_translation(
+            quaternion::axis_angle(vecmath::vec3_normalized([1.0, 2.0, -1.0]), 1.1), [3.0, -2.0, 1.0]
+        );
+        let a: Vector3<f64> = [1.0, 0.0, 2.0];
+        let b = [-1.0, 4.0, 0.5];
+
+        // transforming the line agrees with transforming two of its points
+        let l = super::transform_line(q, Line::from_points(a, b));
+        let expected = Line::from_points(transform_point(q, a), transform_point(q, b));
+        for i in 0..3 {
+            assert!((l.direction[i] - expected.direction[i]).abs() < EPSILON);
+            assert!((l.moment[i] - expected.moment[i]).abs() < EPSILON);
+        }
+        assert!(l.distance_to_point(transform_point(q, a)) < EPSILON);
+    }
+
+    #[test]
+    fn test_distance_and_closest_points() {
+        // the X axis and a line along Y, 2 units above it
+        let a: Line<f64> = Line::from_point_and_direction([0.0, 0.0, 0.0], [1.0, 0.0, 0.0]);
+        let b = Line::from_point_and_direction([5.0, 0.0, 2.0], [0.0, 3.0, 0.0]);
+        assert!((super::distance(a, b) - 2.0).abs() < EPSILON);
+
+        let (pa, pb) = super::closest_points(a, b).unwrap();
+        let (ea, eb) = ([5.0, 0.0, 0.0], [5.0, 0.0, 2.0]);
+        for i in 0..3 {
+            assert!((pa[i] - ea[i]).abs() < EPSILON);
+            assert!((pb[i] - eb[i]).abs() < EPSILON);
+        }
+        assert!(super::intersection(a, b, 0.001).is_none());
+
+        // parallel lines pointing in opposite directions
+        let c = Line::from_point_and_direction([0.0, 3.0, 4.0], [-2.0, 0.0, 0.0]);
+        assert!((super::distance(a, c) - 5.0).abs() < EPSILON);
+        assert!(super::closest_points(a, c).is_none());
+    }
+
+    #[test]
+    fn test_intersect_screw_axes() {
+        // two rotations about different axes through the same point
+        let center = [1.0, -2.0, 3.0];
+        let screw = |axis, angle| {
+            let r = quaternion::axis_angle(vecmath::vec3_normalized(axis), angle);
+            let t = vecmath::vec3_sub(center, quaternion::rotate_vector(r, center));
+            to_screw(from_rotation_and_translation(r, t))
+        };
+        let a = screw([1.0, 0.0, 0.0], PI / 3.0);
+        let b = screw([0.0, 1.0, 1.0], 1.0);
+
+        let p = super::intersect_screw_axes(a, b, 0.000001).unwrap();
+        for i in 0..3 {
+            assert!((p[i] - center[i]).abs() < EPSILON);
+        }
+    }
+
+}
@@ -0,0 +1,105 @@
+//! Square roots and transcendental functions used by the dual-quaternion algorithms
+//!
+//! With the `std` feature these call the methods of `Float`. Without it, or with the
+//! `deterministic` feature, `f32` and `f64` are computed with `libm`, and other float types
+//! fall back to `Float`. The `deterministic` feature also makes `libm` use software
+//! implementations instead of platform instructions, so results are bit-identical across machines.
+
+#[cfg(any(feature = "deterministic", not(feature = "std")))]
+use core::any::Any;
+
+use vecmath::{self, Vector3};
+use vecmath::traits::Float;
+
+/// Calls the `libm` function matching the type of `x`, or `fallback` for other types
+#[cfg(any(feature = "deterministic", not(feature = "std")))]
+#[inline(always)]
+fn libm_or<T: Float>(x: T, f: fn(f32) -> f32, d: fn(f64) -> f64, fallback: fn(T) -> T) -> T {
+    if let Some(&x) = (&x as &dyn Any).downcast_ref::<f32>() {
+        return *(&f(x) as &dyn Any).downcast_ref::<T>().unwrap();
+    }
+    if let Some(&x) = (&x as &dyn Any).downcast_ref::<f64>() {
+        return *(&d(x) as &dyn Any).downcast_ref::<T>().unwrap();
+    }
+    fallback(x)
+}
+
+macro_rules! unary {
+    ($(#[$attr:meta])* $name:ident, $f:ident, $d:ident, $fallback:expr) => {
+        $(#[$attr])*
+        #[cfg(all(feature = "std", not(feature = "deterministic")))]
+        #[inline(always)]
+        pub fn $name<T: Float>(x: T) -> T {
+            $fallback(x)
+        }
+
+        $(#[$attr])*
+        #[cfg(any(feature = "deterministic", not(feature = "std")))]
+        #[inline(always)]
+        pub fn $name<T: Float>(x: T) -> T {
+            libm_or(x, ::libm::$f, ::libm::$d, $fallback)
+        }
+    }
+}
+
+unary!(/// Returns the square root
+       sqrt, sqrtf, sqrt, |x: T| x.sqrt());
+unary!(/// Returns the sine
+       sin, sinf, sin, |x: T| x.sin());
+unary!(/// Returns the cosine
+       cos, cosf, cos, |x: T| x.cos());
+unary!(/// Returns `e^x`
+       exp, expf, exp, |x: T| T::from_f64(::core::f64::consts::E).powf(x));
+
+/// Returns the four quadrant arctangent of `y` and `x`
+#[cfg(all(feature = "std", not(feature = "deterministic")))]
+#[inline(always)]
+pub fn atan2<T: Float>(y: T, x: T) -> T {
+    y.atan2(x)
+}
+
+/// Returns the four quadrant arctangent of `y` and `x`
+#[cfg(any(feature = "deterministic", not(feature = "std")))]
+#[inline(always)]
+pub fn atan2<T: Float>(y: T, x: T) -> T {
+    let any = (&y as &dyn Any, &x as &dyn Any);
+    if let (Some(&y), Some(&x)) = (any.0.downcast_ref::<f32>(), any.1.downcast_ref::<f32>()) {
+        return *(&::libm::atan2f(y, x) as &dyn Any).downcast_ref::<T>().unwrap();
+    }
+    if let (Some(&y), Some(&x)) = (any.0.downcast_ref::<f64>(), any.1.downcast_ref::<f64>()) {
+        return *(&::libm::atan2(y, x) as &dyn Any).downcast_ref::<T>().unwrap();
+    }
+    y.atan2(x)
+}
+
+/// Returns the length of a vector
+#[inline(always)]
+pub fn vec3_len<T: Float>(v: Vector3<T>) -> T {
+    sqrt(vecmath::vec3_square_len(v))
+}
+
+/// Returns a vector with the same direction and unit length
+#[inline(always)]
+pub fn vec3_normalized<T: Float>(v: Vector3<T>) -> Vector3<T> {
+    vecmath::vec3_scale(v, T::one() / vec3_len(v))
+}
+
+/// Tests
+#[cfg(test)]
+mod test {
+
+    #[test]
+    fn test_matches_std() {
+        let xs = [-3.0, -0.5, 0.0, 1e-3, 0.7, 2.0, 10.0];
+        for &x in &xs {
+            let x: f64 = x;
+            assert!((super::sin(x) - x.sin()).abs() < 1e-15);
+            assert!((super::cos(x) - x.cos()).abs() < 1e-15);
+            assert!((super::exp(x) - x.exp()).abs() < 1e-15 * x.exp().max(1.0) * 10.0);
+            assert!((super::atan2(x, 1.5) - x.atan2(1.5)).abs() < 1e-15);
+            assert!((super::sqrt(x.abs()) - x.abs().sqrt()).abs() < 1e-15);
+            assert!((super::sin(x as f32) - (x as f32).sin()).abs() < 1e-6);
+        }
+    }
+
+}
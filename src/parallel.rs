@@ -0,0 +1,257 @@
+//! Parallel versions of the slice operations using rayon, enabled by the `rayon` feature
+//!
+//! Each function computes every element with the same operations as its serial counterpart,
+//! so the results are identical and don't depend on the number of threads.
+
+use alloc::vec::Vec;
+
+use quaternion;
+use rayon::prelude::*;
+use vecmath::{self, Vector3};
+use vecmath::traits::Float;
+
+use skinning::Influences;
+use super::{get_translation, joint_world_transform, transform_point, transform_vector, DualQuaternion};
+
+/// Transforms a slice of points by a unit dual-quaternion in parallel, see `transform_points`
+///
+/// Panics if `src` and `dst` have different lengths.
+pub fn transform_points<T>(q: DualQuaternion<T>, src: &[Vector3<T>], dst: &mut [Vector3<T>])
+    where T: Float
+{
+    assert_eq!(src.len(), dst.len());
+    let t = get_translation(q);
+    dst.par_iter_mut().zip(src.par_iter()).for_each(|(out, p)| {
+        *out = vecmath::vec3_add(quaternion::rotate_vector(q.0, *p), t);
+    });
+}
+
+/// Transforms point `src[i]` by `qs[i]` in parallel, writing the result to `dst[i]`
+///
+/// Panics if the slices have different lengths.
+pub fn transform_points_each<T>(qs: &[DualQuaternion<T>], src: &[Vector3<T>], dst: &mut [Vector3<T>])
+    where T: Float
+{
+    assert_eq!(qs.len(), src.len());
+    assert_eq!(qs.len(), dst.len());
+    dst.par_iter_mut().zip(qs.par_iter().zip(src.par_iter())).for_each(|(out, (q, p))| {
+        *out = transform_point(*q, *p);
+    });
+}
+
+/// Deforms vertex positions in parallel, see `skinning::skin_positions`
+///
+/// Panics if `src`, `dst` and `influences` don't describe the same number of vertices.
+pub fn skin_positions<T>(
+    joints: &[DualQuaternion<T>],
+    influences: &Influences<T>,
+    src: &[Vector3<T>],
+    dst: &mut [Vector3<T>]
+)
+    where T: Float
+{
+    assert_eq!(src.len(), influences.len());
+    assert_eq!(dst.len(), influences.len());
+    dst.par_iter_mut().zip(src.par_iter()).enumerate().for_each(|(v, (out, p))| {
+        *out = transform_point(influences.vertex_transform(joints, v), *p);
+    });
+}
+
+/// Deforms vertex positions and normals in parallel, see `skinning::skin`
+///
+/// Panics if the slices and `influences` don't describe the same number of vertices.
+pub fn skin<T>(
+    joints: &[DualQuaternion<T>],
+    influences: &Influences<T>,
+    positions: &[Vector3<T>],
+    normals: &[Vector3<T>],
+    dst_positions: &mut [Vector3<T>],
+    dst_normals: &mut [Vector3<T>]
+)
+    where T: Float
+{
+    let n = influences.len();
+    assert!(positions.len() == n && normals.len() == n);
+    assert!(dst_positions.len() == n && dst_normals.len() == n);
+    dst_positions.par_iter_mut().zip(dst_normals.par_iter_mut()).enumerate().for_each(|(v, (p, nrm))| {
+        let q = influences.vertex_transform(joints, v);
+        *p = transform_point(q, positions[v]);
+        *nrm = transform_vector(q, normals[v]);
+    });
+}
+
+/// The joints of a hierarchy grouped by depth, for evaluating it in parallel with `world_transforms`
+///
+/// Build it once per hierarchy and reuse it for every evaluation while the parents don't change.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Levels {
+    parents: Vec<Option<usize>>,
+    /// Joint indices sorted by depth, roots first
+    joints: Vec<usize>,
+    /// End of each level in `joints`
+    ends: Vec<usize>,
+    widest: usize,
+}
+
+impl Levels {
+    /// Groups the joints of a hierarchy by depth
+    ///
+    /// `parents[j]` is the parent of joint `j`, or `None` for a root.
+    ///
+    /// Panics if a parent doesn't come before its child.
+    pub fn new(parents: &[Option<usize>]) -> Levels {
+        let mut depths: Vec<usize> = Vec::with_capacity(parents.len());
+        let mut counts: Vec<usize> = Vec::new();
+        for (j, parent) in parents.iter().enumerate() {
+            let depth = match *parent {
+                Some(p) => {
+                    assert!(p < j, "joint {} comes before its parent {}", j, p);
+                    depths[p] + 1
+                }
+                None => 0,
+            };
+            depths.push(depth);
+            if depth == counts.len() {
+                counts.push(0);
+            }
+            counts[depth] += 1;
+        }
+        let mut ends = Vec::with_capacity(counts.len());
+        let mut end = 0;
+        for &count in &counts {
+            end += count;
+            ends.push(end);
+        }
+        // counting sort by depth, keeping the joint order within each level
+        let mut next: Vec<usize> = ends.iter().zip(&counts).map(|(&end, &count)| end - count).collect();
+        let mut joints = alloc::vec![0; parents.len()];
+        for (j, &depth) in depths.iter().enumerate() {
+            joints[next[depth]] = j;
+            next[depth] += 1;
+        }
+        Levels {
+            parents: parents.to_vec(),
+            joints,
+            ends,
+            widest: counts.iter().cloned().max().unwrap_or(0),
+        }
+    }
+
+    /// Returns the number of joints
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Returns `true` if there are no joints
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// Returns the parent of every joint
+    #[inline(always)]
+    pub fn parents(&self) -> &[Option<usize>] {
+        &self.parents
+    }
+
+    /// Returns the number of levels, the depth of the deepest joint plus one
+    #[inline(always)]
+    pub fn depth(&self) -> usize {
+        self.ends.len()
+    }
+}
+
+/// Composes local joint transforms into world transforms in parallel, see `skinning::world_transforms`
+///
+/// Joints are evaluated one depth level at a time, with the joints of each level in parallel,
+/// so wide hierarchies benefit the most. The only allocation is a buffer for the widest level.
+///
+/// Panics if `locals` and `dst` don't have one transform per joint of `levels`.
+pub fn world_transforms<T: Float>(levels: &Levels, locals: &[DualQuaternion<T>], dst: &mut [DualQuaternion<T>]) {
+    assert_eq!(locals.len(), levels.len());
+    assert_eq!(dst.len(), levels.len());
+    let mut level_worlds = Vec::with_capacity(levels.widest);
+    let mut start = 0;
+    for &end in &levels.ends {
+        let level = &levels.joints[start..end];
+        let worlds: &[DualQuaternion<T>] = dst;
+        level.par_iter()
+            .map(|&j| joint_world_transform(&levels.parents, locals, worlds, j))
+            .collect_into_vec(&mut level_worlds);
+        for (&j, q) in level.iter().zip(&level_worlds) {
+            dst[j] = *q;
+        }
+        start = end;
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod test {
+
+    use skinning::{self, Influences};
+    use test_util::{pseudo_random, random_points, random_poses};
+    use super::super::id;
+
+    #[test]
+    fn test_transform_points_matches_serial() {
+        let mut state = 1;
+        let qs = random_poses(1000, &mut state);
+        let src = random_points(1000, &mut state);
+        let (mut serial, mut parallel) = (vec![[0.0; 3]; 1000], vec![[0.0; 3]; 1000]);
+
+        super::super::transform_points(qs[0], &src, &mut serial);
+        super::transform_points(qs[0], &src, &mut parallel);
+        assert_eq!(serial, parallel);
+
+        for (out, (q, p)) in serial.iter_mut().zip(qs.iter().zip(&src)) {
+            *out = super::super::transform_point(*q, *p);
+        }
+        super::transform_points_each(&qs, &src, &mut parallel);
+        assert_eq!(serial, parallel);
+    }
+
+    #[test]
+    fn test_skin_matches_serial() {
+        let mut state = 2;
+        let joints = random_poses(20, &mut state);
+        let indices: Vec<usize> = (0..3000).map(|i| (i * 7) % 20).collect();
+        let weights: Vec<f64> = (0..3000).map(|_| pseudo_random::<f64>(&mut state).abs()).collect();
+        let influences = Influences::new(&indices, &weights, 3);
+        let positions = random_points(1000, &mut state);
+        let normals = random_points(1000, &mut state);
+
+        let (mut serial_p, mut serial_n) = (vec![[0.0; 3]; 1000], vec![[0.0; 3]; 1000]);
+        let (mut parallel_p, mut parallel_n) = (vec![[0.0; 3]; 1000], vec![[0.0; 3]; 1000]);
+        skinning::skin(&joints, &influences, &positions, &normals, &mut serial_p, &mut serial_n);
+        super::skin(&joints, &influences, &positions, &normals, &mut parallel_p, &mut parallel_n);
+        assert_eq!(serial_p, parallel_p);
+        assert_eq!(serial_n, parallel_n);
+
+        skinning::skin_positions(&joints, &influences, &positions, &mut serial_p);
+        super::skin_positions(&joints, &influences, &positions, &mut parallel_p);
+        assert_eq!(serial_p, parallel_p);
+    }
+
+    #[test]
+    fn test_world_transforms_matches_serial() {
+        let mut state = 3;
+        let locals = random_poses::<f64>(500, &mut state);
+        // several roots with branching chains
+        let parents: Vec<Option<usize>> = (0..500).map(|j| if j % 100 == 0 { None } else { Some(j / 2) }).collect();
+        let (mut serial, mut parallel) = (vec![id(); 500], vec![id(); 500]);
+        skinning::world_transforms(&parents, &locals, &mut serial);
+        let levels = super::Levels::new(&parents);
+        assert_eq!(levels.depth(), 10);
+        super::world_transforms(&levels, &locals, &mut parallel);
+        assert_eq!(serial, parallel);
+
+        // the levels are reused for another pose
+        let locals = random_poses(500, &mut state);
+        skinning::world_transforms(&parents, &locals, &mut serial);
+        super::world_transforms(&levels, &locals, &mut parallel);
+        assert_eq!(serial, parallel);
+    }
+
+}
@@ -0,0 +1,215 @@
+//! Serialization of dual-quaternions with serde, enabled by the `serde` feature
+//!
+//! Two layouts are supported:
+//!
+//! - The compact layout is an array of 8 numbers: the real part followed by the dual part,
+//!   each as `[w, x, y, z]`. It stores any dual-quaternion exactly.
+//! - The rotation-translation layout is a map `{ "rotation": [w, x, y, z], "translation": [x, y, z] }`.
+//!   It is meant for human-readable formats and only stores unit dual-quaternions.
+//!
+//! `DualQuat<T>` always uses the compact layout. `UnitDualQuaternion<T>` uses the
+//! rotation-translation layout for human-readable formats and the compact layout otherwise,
+//! and fails to deserialize values that are not unit dual-quaternions.
+//!
+//! The `DualQuaternion<T>` tuple can pick a layout with `#[serde(with = "...")]`
+//! using one of the modules below. The `_unit` variants validate unit-ness on deserialization.
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use vecmath::traits::Float;
+
+use unit::UnitDualQuaternion;
+use super::{from_rotation_and_translation, get_rotation, get_translation, DualQuat, DualQuaternion};
+
+#[derive(Serialize, Deserialize)]
+struct RotationTranslation<T> {
+    rotation: [T; 4],
+    translation: [T; 3],
+}
+
+#[inline(always)]
+fn to_array<T: Float>(q: DualQuaternion<T>) -> [T; 8] {
+    let ((a, [b, c, d]), (e, [f, g, h])) = q;
+    [a, b, c, d, e, f, g, h]
+}
+
+#[inline(always)]
+fn from_array<T: Float>(a: [T; 8]) -> DualQuaternion<T> {
+    ((a[0], [a[1], a[2], a[3]]), (a[4], [a[5], a[6], a[7]]))
+}
+
+#[inline(always)]
+fn to_rotation_translation<T: Float>(q: DualQuaternion<T>) -> RotationTranslation<T> {
+    let (w, [x, y, z]) = get_rotation(q);
+    RotationTranslation {
+        rotation: [w, x, y, z],
+        translation: get_translation(q),
+    }
+}
+
+#[inline(always)]
+fn from_rotation_translation<T: Float>(rt: RotationTranslation<T>) -> DualQuaternion<T> {
+    let [w, x, y, z] = rt.rotation;
+    from_rotation_and_translation((w, [x, y, z]), rt.translation)
+}
+
+fn validate<T: Float, E: Error>(q: DualQuaternion<T>) -> Result<DualQuaternion<T>, E> {
+    UnitDualQuaternion::new(q).map(|u| u.into_inner()).map_err(E::custom)
+}
+
+/// Compact layout for `DualQuaternion<T>`, an array of 8 numbers
+pub mod compact {
+    use super::*;
+
+    /// Serializes a dual-quaternion as an array of 8 numbers
+    pub fn serialize<T, S>(q: &DualQuaternion<T>, serializer: S) -> Result<S::Ok, S::Error>
+        where T: Float + Serialize, S: Serializer
+    {
+        to_array(*q).serialize(serializer)
+    }
+
+    /// Deserializes a dual-quaternion from an array of 8 numbers
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<DualQuaternion<T>, D::Error>
+        where T: Float + Deserialize<'de>, D: Deserializer<'de>
+    {
+        <[T; 8]>::deserialize(deserializer).map(from_array)
+    }
+}
+
+/// Compact layout for `DualQuaternion<T>`, rejecting non-unit dual-quaternions on deserialization
+pub mod compact_unit {
+    use super::*;
+
+    pub use super::compact::serialize;
+
+    /// Deserializes a unit dual-quaternion from an array of 8 numbers
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<DualQuaternion<T>, D::Error>
+        where T: Float + Deserialize<'de>, D: Deserializer<'de>
+    {
+        validate(super::compact::deserialize(deserializer)?)
+    }
+}
+
+/// Rotation-translation layout for unit `DualQuaternion<T>` values
+pub mod rotation_translation {
+    use super::*;
+
+    /// Serializes a unit dual-quaternion as its rotation and translation
+    pub fn serialize<T, S>(q: &DualQuaternion<T>, serializer: S) -> Result<S::Ok, S::Error>
+        where T: Float + Serialize, S: Serializer
+    {
+        to_rotation_translation(*q).serialize(serializer)
+    }
+
+    /// Deserializes a dual-quaternion from its rotation and translation
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<DualQuaternion<T>, D::Error>
+        where T: Float + Deserialize<'de>, D: Deserializer<'de>
+    {
+        RotationTranslation::deserialize(deserializer).map(from_rotation_translation)
+    }
+}
+
+/// Rotation-translation layout for `DualQuaternion<T>`, rejecting non-unit rotations on deserialization
+pub mod rotation_translation_unit {
+    use super::*;
+
+    pub use super::rotation_translation::serialize;
+
+    /// Deserializes a unit dual-quaternion from its rotation and translation
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<DualQuaternion<T>, D::Error>
+        where T: Float + Deserialize<'de>, D: Deserializer<'de>
+    {
+        validate(super::rotation_translation::deserialize(deserializer)?)
+    }
+}
+
+impl<T: Float + Serialize> Serialize for DualQuat<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        compact::serialize(&(*self).into(), serializer)
+    }
+}
+
+impl<'de, T: Float + Deserialize<'de>> Deserialize<'de> for DualQuat<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<DualQuat<T>, D::Error> {
+        compact::deserialize(deserializer).map(DualQuat::from)
+    }
+}
+
+impl<T: Float + Serialize> Serialize for UnitDualQuaternion<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            rotation_translation::serialize(self.as_ref(), serializer)
+        } else {
+            compact::serialize(self.as_ref(), serializer)
+        }
+    }
+}
+
+impl<'de, T: Float + Deserialize<'de>> Deserialize<'de> for UnitDualQuaternion<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<UnitDualQuaternion<T>, D::Error> {
+        let q = if deserializer.is_human_readable() {
+            rotation_translation_unit::deserialize(deserializer)?
+        } else {
+            compact_unit::deserialize(deserializer)?
+        };
+        Ok(UnitDualQuaternion::new_unchecked(q))
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod test {
+
+    use quaternion;
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+
+    use super::super::{from_rotation_and_translation, scale, DualQuat, DualQuaternion, UnitDualQuaternion};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Pose {
+        #[serde(with = "super::compact")]
+        compact: DualQuaternion<f64>,
+        #[serde(with = "super::rotation_translation_unit")]
+        readable: DualQuaternion<f64>,
+    }
+
+    #[test]
+    fn test_compact_layout() {
+        let q = DualQuat::new((1.0, [2.0, 3.0, 4.0]), (5.0, [6.0, 7.0, 8.0]));
+        let json = serde_json::to_string(&q).unwrap();
+        assert_eq!(json, "[1.0,2.0,3.0,4.0,5.0,6.0,7.0,8.0]");
+        assert_eq!(serde_json::from_str::<DualQuat<f64>>(&json).unwrap(), q);
+        assert!(serde_json::from_str::<DualQuat<f64>>("[1.0,2.0]").is_err());
+    }
+
+    #[test]
+    fn test_rotation_translation_layout() {
+        let q = UnitDualQuaternion::from_rotation_and_translation((0.0, [0.0, 1.0, 0.0]), [1.0, 2.0, 3.0]);
+        let json = serde_json::to_string(&q).unwrap();
+        assert_eq!(json, r#"{"rotation":[0.0,0.0,1.0,0.0],"translation":[1.0,2.0,3.0]}"#);
+        assert_eq!(serde_json::from_str::<UnitDualQuaternion<f64>>(&json).unwrap(), q);
+
+        // non-unit rotations are rejected
+        let json = r#"{"rotation":[2.0,0.0,0.0,0.0],"translation":[1.0,2.0,3.0]}"#;
+        assert!(serde_json::from_str::<UnitDualQuaternion<f64>>(json).is_err());
+    }
+
+    #[test]
+    fn test_with_modules() {
+        let q = from_rotation_and_translation(quaternion::axis_angle([1.0, 0.0, 0.0], 0.5), [1.0, -2.0, 0.25]);
+        let pose = Pose { compact: scale(q, 2.0), readable: q };
+        let json = serde_json::to_string(&pose).unwrap();
+        let pose_prime: Pose = serde_json::from_str(&json).unwrap();
+        for (a, b) in super::to_array(pose_prime.compact).iter().zip(&super::to_array(pose.compact)) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        for (a, b) in super::to_array(pose_prime.readable).iter().zip(&super::to_array(q)) {
+            assert!((a - b).abs() < 1e-12);
+        }
+
+        let json = r#"{"compact":[1,0,0,0,0,0,0,0],"readable":{"rotation":[1,1,0,0],"translation":[0,0,0]}}"#;
+        assert!(serde_json::from_str::<Pose>(json).is_err());
+    }
+
+}
@@ -0,0 +1,262 @@
+//! Joint hierarchies evaluated from local to world space
+
+use alloc::vec::Vec;
+
+use vecmath::traits::Float;
+
+use skinning;
+use super::{id, inverse, joint_world_transform, mul, DualQuaternion};
+
+/// A joint hierarchy with local transforms, inverse bind poses and the derived
+/// world and skinning transforms
+///
+/// Joints are stored in topological order: every parent comes before its children.
+/// Changing a local transform marks the joint dirty, and `update` re-evaluates
+/// only the dirty joints and their descendants.
+/// Changing an inverse bind pose only re-evaluates the skinning transform of that joint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Skeleton<T> {
+    parents: Vec<Option<usize>>,
+    locals: Vec<DualQuaternion<T>>,
+    inverse_bind_poses: Vec<DualQuaternion<T>>,
+    worlds: Vec<DualQuaternion<T>>,
+    skinning: Vec<DualQuaternion<T>>,
+    dirty: Vec<bool>,
+    rebind: Vec<bool>,
+}
+
+impl<T: Float> Skeleton<T> {
+    /// Creates a skeleton from parent indices and local transforms, with identity inverse bind poses
+    ///
+    /// `parents[j]` is the parent of joint `j`, or `None` for a root.
+    /// The world and skinning transforms are evaluated immediately.
+    ///
+    /// Panics if the lengths differ or a parent doesn't come before its child.
+    pub fn new(parents: Vec<Option<usize>>, locals: Vec<DualQuaternion<T>>) -> Skeleton<T> {
+        assert_eq!(parents.len(), locals.len());
+        for (j, parent) in parents.iter().enumerate() {
+            if let Some(p) = *parent {
+                assert!(p < j, "joint {} comes before its parent {}", j, p);
+            }
+        }
+        let n = locals.len();
+        let mut skeleton = Skeleton {
+            parents,
+            locals,
+            inverse_bind_poses: alloc::vec![id(); n],
+            worlds: alloc::vec![id(); n],
+            skinning: alloc::vec![id(); n],
+            dirty: alloc::vec![true; n],
+            rebind: alloc::vec![false; n],
+        };
+        skeleton.update();
+        skeleton
+    }
+
+    /// Returns the number of joints
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Returns `true` if there are no joints
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// Returns the parent of every joint
+    #[inline(always)]
+    pub fn parents(&self) -> &[Option<usize>] {
+        &self.parents
+    }
+
+    /// Returns the local transform of every joint, relative to its parent
+    #[inline(always)]
+    pub fn locals(&self) -> &[DualQuaternion<T>] {
+        &self.locals
+    }
+
+    /// Replaces the local transform of a joint and marks it dirty
+    pub fn set_local(&mut self, joint: usize, q: DualQuaternion<T>) {
+        self.locals[joint] = q;
+        self.dirty[joint] = true;
+    }
+
+    /// Returns the inverse bind pose of every joint
+    #[inline(always)]
+    pub fn inverse_bind_poses(&self) -> &[DualQuaternion<T>] {
+        &self.inverse_bind_poses
+    }
+
+    /// Replaces the inverse bind pose of a joint
+    ///
+    /// The next `update` re-evaluates the skinning transform of this joint only.
+    pub fn set_inverse_bind_pose(&mut self, joint: usize, q: DualQuaternion<T>) {
+        self.inverse_bind_poses[joint] = q;
+        self.rebind[joint] = true;
+    }
+
+    /// Sets the inverse bind poses from a rest pose given as local transforms
+    ///
+    /// The rest pose becomes the bind pose, so skinning transforms are the identity in it.
+    /// The current local transforms are kept.
+    ///
+    /// Panics if `rest` doesn't have one transform per joint.
+    pub fn bind(&mut self, rest: &[DualQuaternion<T>]) {
+        assert_eq!(rest.len(), self.len());
+        let mut rest_worlds = alloc::vec![id(); rest.len()];
+        skinning::world_transforms(&self.parents, rest, &mut rest_worlds);
+        for (inverse_bind_pose, &world) in self.inverse_bind_poses.iter_mut().zip(&rest_worlds) {
+            *inverse_bind_pose = inverse(world);
+        }
+        for r in &mut self.rebind {
+            *r = true;
+        }
+    }
+
+    /// Sets the inverse bind poses from the current local transforms, see `bind`
+    pub fn bind_current_pose(&mut self) {
+        let rest = self.locals.clone();
+        self.bind(&rest);
+    }
+
+    /// Returns `true` if some joints changed since the last `update`
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.iter().chain(&self.rebind).any(|&d| d)
+    }
+
+    /// Re-evaluates the world and skinning transforms of dirty joints and their descendants,
+    /// and the skinning transforms of joints with a new inverse bind pose
+    pub fn update(&mut self) {
+        for j in 0..self.len() {
+            let parent = self.parents[j];
+            let dirty = self.dirty[j] || parent.is_some_and(|p| self.dirty[p]);
+            if dirty {
+                // propagates to the descendants, which come later
+                self.dirty[j] = true;
+                self.worlds[j] = joint_world_transform(&self.parents, &self.locals, &self.worlds, j);
+            } else if !self.rebind[j] {
+                continue;
+            }
+            self.skinning[j] = mul(self.worlds[j], self.inverse_bind_poses[j]);
+        }
+        for d in self.dirty.iter_mut().chain(&mut self.rebind) {
+            *d = false;
+        }
+    }
+
+    /// Returns the world transform of every joint as of the last `update`
+    #[inline(always)]
+    pub fn world_transforms(&self) -> &[DualQuaternion<T>] {
+        &self.worlds
+    }
+
+    /// Returns the skinning transform of every joint as of the last `update`,
+    /// the world transform times the inverse bind pose
+    ///
+    /// These are the joint transforms expected by the `skinning` module.
+    #[inline(always)]
+    pub fn skinning_transforms(&self) -> &[DualQuaternion<T>] {
+        &self.skinning
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod test {
+
+    use std::f64::consts::PI;
+    use quaternion;
+
+    use skinning;
+    use super::Skeleton;
+    use super::super::{from_rotation_and_translation, id, mul, transform_point, DualQuaternion};
+
+    const EPSILON: f64 = 0.000000001;
+
+    fn locals(angle: f64) -> std::vec::Vec<DualQuaternion<f64>> {
+        vec![
+            from_rotation_and_translation(quaternion::axis_angle([0.0, 0.0, 1.0], PI / 2.0), [0.0, 0.0, 1.0]),
+            from_rotation_and_translation(quaternion::axis_angle([1.0, 0.0, 0.0], angle), [1.0, 0.0, 0.0]),
+            from_rotation_and_translation(quaternion::id(), [0.0, 2.0, 0.0]),
+            from_rotation_and_translation(quaternion::id(), [0.0, 0.0, 3.0]),
+        ]
+    }
+
+    #[test]
+    fn test_world_transforms() {
+        // joint 3 is a sibling of joint 1
+        let parents = vec![None, Some(0), Some(1), Some(0)];
+        let skeleton = Skeleton::new(parents.clone(), locals(0.0));
+        let mut expected = vec![id(); 4];
+        skinning::world_transforms(&parents, &locals(0.0), &mut expected);
+        assert_eq!(skeleton.world_transforms(), &expected[..]);
+
+        let p = transform_point(skeleton.world_transforms()[2], [0.0, 0.0, 0.0]);
+        let expected = [-2.0, 1.0, 1.0];
+        for i in 0..3 {
+            assert!((p[i] - expected[i]).abs() < EPSILON);
+        }
+        assert_eq!(skeleton.skinning_transforms(), skeleton.world_transforms());
+    }
+
+    #[test]
+    fn test_bind_and_update() {
+        let parents = vec![None, Some(0), Some(1), Some(0)];
+        let mut skeleton = Skeleton::new(parents.clone(), locals(0.0));
+        skeleton.bind_current_pose();
+        skeleton.update();
+        for q in skeleton.skinning_transforms() {
+            let p = transform_point(*q, [1.0, 2.0, 3.0]);
+            assert!((p[0] - 1.0).abs() < EPSILON && (p[1] - 2.0).abs() < EPSILON && (p[2] - 3.0).abs() < EPSILON);
+        }
+
+        // only the subtree of joint 1 changes, so sentinels in the clean joints 0 and 3 survive
+        let sentinel = from_rotation_and_translation(quaternion::id(), [7.0, 7.0, 7.0]);
+        skeleton.set_local(1, locals(0.5)[1]);
+        assert!(skeleton.is_dirty());
+        skeleton.worlds[0] = sentinel;
+        skeleton.worlds[3] = sentinel;
+        skeleton.update();
+        assert!(!skeleton.is_dirty());
+        assert_eq!(skeleton.world_transforms()[0], sentinel);
+        assert_eq!(skeleton.world_transforms()[3], sentinel);
+        // joint 1 is evaluated from the sentinel world transform of its parent
+        let expected_1 = mul(sentinel, locals(0.5)[1]);
+        assert_eq!(skeleton.world_transforms()[1], expected_1);
+        assert_eq!(skeleton.world_transforms()[2], mul(expected_1, locals(0.5)[2]));
+
+        // a clean update changes nothing
+        skeleton.worlds[2] = sentinel;
+        skeleton.update();
+        assert_eq!(skeleton.world_transforms()[2], sentinel);
+    }
+
+    #[test]
+    fn test_set_inverse_bind_pose() {
+        let parents = vec![None, Some(0), Some(1), Some(0)];
+        let mut skeleton = Skeleton::new(parents, locals(0.0));
+        let sentinel = from_rotation_and_translation(quaternion::id(), [7.0, 7.0, 7.0]);
+        let inverse_bind_pose = from_rotation_and_translation(quaternion::id(), [0.0, -1.0, 0.0]);
+        skeleton.set_inverse_bind_pose(1, inverse_bind_pose);
+        assert!(skeleton.is_dirty());
+        skeleton.worlds[1] = sentinel;
+        skeleton.worlds[2] = sentinel;
+        skeleton.skinning[2] = sentinel;
+        skeleton.update();
+        assert!(!skeleton.is_dirty());
+        // only the skinning transform of joint 1 is re-evaluated, not the worlds or the subtree
+        assert_eq!(skeleton.world_transforms()[1], sentinel);
+        assert_eq!(skeleton.world_transforms()[2], sentinel);
+        assert_eq!(skeleton.skinning_transforms()[1], mul(sentinel, inverse_bind_pose));
+        assert_eq!(skeleton.skinning_transforms()[2], sentinel);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_unordered_parents() {
+        Skeleton::new(vec![Some(1), None], vec![id::<f32>(); 2]);
+    }
+
+}
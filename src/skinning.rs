@@ -0,0 +1,243 @@
+//! Mesh skinning with dual-quaternion linear blending
+
+use vecmath::Vector3;
+use vecmath::traits::Float;
+
+use super::{blend, id, joint_world_transform, transform_point, transform_vector, DualQuaternion};
+
+/// Maximum number of joint influences per vertex
+pub const MAX_INFLUENCES: usize = 8;
+
+/// Per-vertex joint indices and weights, stored as flat slices
+/// with a fixed number of influences per vertex
+#[derive(Clone, Copy, Debug)]
+pub struct Influences<'a, T: 'a> {
+    indices: &'a [usize],
+    weights: &'a [T],
+    per_vertex: usize,
+}
+
+impl<'a, T: Float> Influences<'a, T> {
+    /// Creates influences from joint indices and weights, `per_vertex` entries for each vertex
+    ///
+    /// Panics if `per_vertex` is zero or greater than `MAX_INFLUENCES`,
+    /// or if the slices don't hold the same whole number of vertices.
+    pub fn new(indices: &'a [usize], weights: &'a [T], per_vertex: usize) -> Influences<'a, T> {
+        assert!(per_vertex > 0 && per_vertex <= MAX_INFLUENCES);
+        assert_eq!(indices.len(), weights.len());
+        assert_eq!(indices.len() % per_vertex, 0);
+        Influences {
+            indices,
+            weights,
+            per_vertex,
+        }
+    }
+
+    /// Returns the number of vertices
+    pub fn len(&self) -> usize {
+        self.indices.len() / self.per_vertex
+    }
+
+    /// Returns `true` if there are no vertices
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Returns the number of influences per vertex
+    pub fn per_vertex(&self) -> usize {
+        self.per_vertex
+    }
+
+    /// Blends the joint transforms influencing the given vertex
+    ///
+    /// The first influence of each vertex is used as the blending pivot,
+    /// so it should be the one with the largest weight.
+    /// A vertex whose weights are all zero gets the identity, so it stays in place.
+    pub fn vertex_transform(&self, joints: &[DualQuaternion<T>], vertex: usize) -> DualQuaternion<T> {
+        let start = vertex * self.per_vertex;
+        let end = start + self.per_vertex;
+        let mut buf = [(id(), T::zero()); MAX_INFLUENCES];
+        for (b, (&i, &w)) in buf.iter_mut().zip(self.indices[start..end].iter().zip(&self.weights[start..end])) {
+            *b = (joints[i], w);
+        }
+        blend(&buf[..self.per_vertex])
+    }
+}
+
+/// Deforms vertex positions by the blended joint transforms, writing the results to `dst`
+///
+/// Panics if `src`, `dst` and `influences` don't describe the same number of vertices.
+pub fn skin_positions<T: Float>(
+    joints: &[DualQuaternion<T>],
+    influences: &Influences<T>,
+    src: &[Vector3<T>],
+    dst: &mut [Vector3<T>]
+) {
+    assert_eq!(src.len(), influences.len());
+    assert_eq!(dst.len(), influences.len());
+    for (v, (p, out)) in src.iter().zip(dst.iter_mut()).enumerate() {
+        *out = transform_point(influences.vertex_transform(joints, v), *p);
+    }
+}
+
+/// Deforms vertex positions and normals by the blended joint transforms,
+/// writing the results to `dst_positions` and `dst_normals`
+///
+/// Panics if the slices and `influences` don't describe the same number of vertices.
+pub fn skin<T: Float>(
+    joints: &[DualQuaternion<T>],
+    influences: &Influences<T>,
+    positions: &[Vector3<T>],
+    normals: &[Vector3<T>],
+    dst_positions: &mut [Vector3<T>],
+    dst_normals: &mut [Vector3<T>]
+) {
+    let n = influences.len();
+    assert!(positions.len() == n && normals.len() == n);
+    assert!(dst_positions.len() == n && dst_normals.len() == n);
+    for v in 0..n {
+        let q = influences.vertex_transform(joints, v);
+        dst_positions[v] = transform_point(q, positions[v]);
+        dst_normals[v] = transform_vector(q, normals[v]);
+    }
+}
+
+/// Composes local joint transforms into world transforms, writing the results to `dst`
+///
+/// `parents[j]` is the parent of joint `j`, or `None` for a root, whose world transform is its local one.
+/// Every parent must come before its children.
+///
+/// Panics if the slices have different lengths or a parent doesn't come before its child.
+pub fn world_transforms<T: Float>(
+    parents: &[Option<usize>],
+    locals: &[DualQuaternion<T>],
+    dst: &mut [DualQuaternion<T>]
+) {
+    assert_eq!(parents.len(), locals.len());
+    assert_eq!(dst.len(), locals.len());
+    for j in 0..locals.len() {
+        dst[j] = joint_world_transform(parents, locals, dst, j);
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod test {
+
+    use std::f64::consts::PI;
+    use quaternion;
+    use vecmath;
+    use vecmath::Vector3;
+
+    use super::Influences;
+    use super::super::{blend, from_rotation_and_translation, id, transform_point, transform_vector};
+
+    const EPSILON: f64 = 0.000000001;
+
+    #[test]
+    fn test_skin_single_influence() {
+        let joints = [
+            from_rotation_and_translation(quaternion::axis_angle([0.0, 0.0, 1.0], PI / 2.0), [1.0, 0.0, 0.0]),
+            from_rotation_and_translation(quaternion::id(), [0.0, 5.0, 0.0]),
+        ];
+        let indices = [1, 0];
+        let weights = [1.0, 1.0];
+        let influences = Influences::new(&indices, &weights, 1);
+
+        let positions: [Vector3<f64>; 2] = [[1.0, 2.0, 3.0], [1.0, 0.0, 0.0]];
+        let normals: [Vector3<f64>; 2] = [[0.0, 0.0, 1.0], [1.0, 0.0, 0.0]];
+        let mut dst_positions = [[0.0; 3]; 2];
+        let mut dst_normals = [[0.0; 3]; 2];
+        super::skin(&joints, &influences, &positions, &normals, &mut dst_positions, &mut dst_normals);
+
+        for v in 0..2 {
+            let q = joints[indices[v]];
+            let p = transform_point(q, positions[v]);
+            let n = transform_vector(q, normals[v]);
+            for i in 0..3 {
+                assert!((dst_positions[v][i] - p[i]).abs() < EPSILON);
+                assert!((dst_normals[v][i] - n[i]).abs() < EPSILON);
+            }
+        }
+    }
+
+    #[test]
+    fn test_skin_blended() {
+        let joints = [
+            from_rotation_and_translation(quaternion::id(), [0.0, 0.0, 0.0]),
+            from_rotation_and_translation(quaternion::axis_angle([1.0, 0.0, 0.0], PI / 2.0), [0.0, 0.0, 0.0]),
+            from_rotation_and_translation(quaternion::id(), [0.0, 0.0, 2.0]),
+        ];
+        let indices = [0, 1, 2, 1, 0, 2];
+        let weights = [0.5, 0.5, 0.0, 0.25, 0.25, 0.5];
+        let influences = Influences::new(&indices, &weights, 3);
+        assert_eq!(influences.len(), 2);
+
+        let src: [Vector3<f64>; 2] = [[0.0, 1.0, 0.0], [3.0, 1.0, 0.0]];
+        let mut dst = [[0.0; 3]; 2];
+        super::skin_positions(&joints, &influences, &src, &mut dst);
+
+        for v in 0..2 {
+            let q = blend(&[
+                (joints[indices[v * 3]], weights[v * 3]),
+                (joints[indices[v * 3 + 1]], weights[v * 3 + 1]),
+                (joints[indices[v * 3 + 2]], weights[v * 3 + 2]),
+            ]);
+            let p = transform_point(q, src[v]);
+            for i in 0..3 {
+                assert!((dst[v][i] - p[i]).abs() < EPSILON);
+            }
+        }
+
+        // half way through the quarter turn about X, the vertex stays on the unit circle
+        let len = vecmath::vec3_len(dst[0]);
+        assert!((len - 1.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_skin_zero_weights() {
+        let joints = [from_rotation_and_translation(quaternion::axis_angle([0.0, 0.0, 1.0], 1.0), [1.0, 2.0, 3.0])];
+        let indices = [0, 0];
+        let weights = [0.0, 0.0];
+        let influences = Influences::new(&indices, &weights, 2);
+        let src: [Vector3<f64>; 1] = [[1.0, -2.0, 0.5]];
+        let mut dst = [[0.0; 3]; 1];
+        super::skin_positions(&joints, &influences, &src, &mut dst);
+        assert_eq!(dst, src);
+    }
+
+    #[test]
+    fn test_world_transforms() {
+        let locals = [
+            from_rotation_and_translation(quaternion::axis_angle([0.0, 0.0, 1.0], PI / 2.0), [0.0, 0.0, 1.0]),
+            from_rotation_and_translation(quaternion::id(), [1.0, 0.0, 0.0]),
+            from_rotation_and_translation(quaternion::id(), [0.0, 2.0, 0.0]),
+        ];
+        let mut worlds = [id(); 3];
+        super::world_transforms(&[None, Some(0), Some(1)], &locals, &mut worlds);
+
+        // the chain rotates a quarter turn about Z at the root, then translates along X and Y
+        let p = transform_point(worlds[2], [0.0, 0.0, 0.0]);
+        let expected = [-2.0, 1.0, 1.0];
+        for i in 0..3 {
+            assert!((p[i] - expected[i]).abs() < EPSILON);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_world_transforms_unordered() {
+        let locals = [id::<f32>(); 2];
+        let mut worlds = [id(); 2];
+        super::world_transforms(&[Some(1), None], &locals, &mut worlds);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_too_many_influences() {
+        let indices = [0; 9];
+        let weights = [0.0f32; 9];
+        Influences::new(&indices, &weights, 9);
+    }
+
+}
@@ -0,0 +1,234 @@
+//! Smooth curves through unit dual-quaternion keys
+//!
+//! Screw linear interpolation between neighbouring keys only gives a continuous path,
+//! with velocity jumps at the keys. The curves here also match velocities at the keys:
+//!
+//! - `bezier` evaluates a cubic Bézier curve with the de Casteljau construction using `sclerp`.
+//! - `kochanek_bartels` and `catmull_rom` derive Bézier control points from the neighbouring keys,
+//!   with tangents computed in the twist space given by `log`. They assume uniformly spaced keys;
+//!   `animation::Track` handles non-uniform key times with the Barry-Goldman construction instead.
+//! - `squad` blends two screw interpolations with control points computed with `log` and `exp`.
+//!
+//! The functions for one segment take four keys and interpolate between the middle two.
+//! Keys are aligned to the hemisphere of their neighbours first, and `path` evaluates
+//! a curve through any number of keys.
+
+use vecmath;
+use vecmath::traits::Float;
+
+use super::{between, dot, exp, log, mul, scale, sclerp, DualQuaternion, Twist};
+
+/// Aligns each key to the hemisphere of the one before it
+#[inline(always)]
+fn align<T: Float>(mut q: [DualQuaternion<T>; 4]) -> [DualQuaternion<T>; 4] {
+    for i in 1..4 {
+        if dot(q[i - 1], q[i]) < T::zero() {
+            q[i] = scale(q[i], -T::one());
+        }
+    }
+    q
+}
+
+#[inline(always)]
+fn twist_scale<T: Float>(v: Twist<T>, s: T) -> Twist<T> {
+    (vecmath::vec3_scale(v.0, s), vecmath::vec3_scale(v.1, s))
+}
+
+#[inline(always)]
+fn twist_add<T: Float>(a: Twist<T>, b: Twist<T>) -> Twist<T> {
+    (vecmath::vec3_add(a.0, b.0), vecmath::vec3_add(a.1, b.1))
+}
+
+/// Returns the tangent at a key as a weighted sum of the incoming and outgoing twists
+#[inline(always)]
+fn tangent<T: Float>(incoming: Twist<T>, outgoing: Twist<T>, a: T, b: T) -> Twist<T> {
+    twist_add(twist_scale(incoming, a), twist_scale(outgoing, b))
+}
+
+/// Evaluates a cubic Bézier curve with control points `p` at `t` in `[0, 1]`
+///
+/// The curve starts at `p[0]`, ends at `p[3]` and is tangent there to the screw
+/// motions towards `p[1]` and from `p[2]`.
+pub fn bezier<T: Float>(p: [DualQuaternion<T>; 4], t: T) -> DualQuaternion<T> {
+    let p = align(p);
+    let a = [sclerp(p[0], p[1], t), sclerp(p[1], p[2], t), sclerp(p[2], p[3], t)];
+    let b = [sclerp(a[0], a[1], t), sclerp(a[1], a[2], t)];
+    sclerp(b[0], b[1], t)
+}
+
+/// Evaluates a Kochanek-Bartels spline between `q[1]` and `q[2]` at `t` in `[0, 1]`
+///
+/// `tension` shortens the tangents, `continuity` lets the incoming and outgoing tangents differ
+/// and `bias` weights the tangents towards the incoming or outgoing segment.
+/// All zero gives a Catmull-Rom spline, which has continuous velocity at the keys.
+pub fn kochanek_bartels<T: Float>(
+    q: [DualQuaternion<T>; 4],
+    t: T,
+    tension: T,
+    continuity: T,
+    bias: T
+) -> DualQuaternion<T> {
+    let q = align(q);
+    let (one, half) = (T::one(), T::from_f64(0.5));
+    let v = [log(between(q[0], q[1])), log(between(q[1], q[2])), log(between(q[2], q[3]))];
+    let k = (one - tension) * half;
+    let outgoing = tangent(
+        v[0], v[1],
+        k * (one + bias) * (one - continuity),
+        k * (one - bias) * (one + continuity)
+    );
+    let incoming = tangent(
+        v[1], v[2],
+        k * (one + bias) * (one + continuity),
+        k * (one - bias) * (one - continuity)
+    );
+    let third = one / T::from_f64(3.0);
+    bezier([
+        q[1],
+        mul(q[1], exp(twist_scale(outgoing, third))),
+        mul(q[2], exp(twist_scale(incoming, -third))),
+        q[2]
+    ], t)
+}
+
+/// Evaluates a uniform Catmull-Rom spline between `q[1]` and `q[2]` at `t` in `[0, 1]`, see `kochanek_bartels`
+#[inline(always)]
+pub fn catmull_rom<T: Float>(q: [DualQuaternion<T>; 4], t: T) -> DualQuaternion<T> {
+    let zero = T::zero();
+    kochanek_bartels(q, t, zero, zero, zero)
+}
+
+/// Evaluates a spherical quadrangle curve between `q[1]` and `q[2]` at `t` in `[0, 1]`
+///
+/// This is the SQUAD construction, generalized to screw motions: the control point
+/// of each key averages the twists towards its neighbours, which gives continuous velocity.
+pub fn squad<T: Float>(q: [DualQuaternion<T>; 4], t: T) -> DualQuaternion<T> {
+    let q = align(q);
+    let quarter = T::from_f64(0.25);
+    let control = |prev, key, next| {
+        let v = twist_add(log(between(key, next)), log(between(key, prev)));
+        mul(key, exp(twist_scale(v, -quarter)))
+    };
+    let s1 = control(q[0], q[1], q[2]);
+    let s2 = control(q[1], q[2], q[3]);
+    let two = T::from_f64(2.0);
+    sclerp(sclerp(q[1], q[2], t), sclerp(s1, s2, t), two * t * (T::one() - t))
+}
+
+/// Evaluates a curve through `keys` at `t`, where key `i` is reached at `t = i`
+///
+/// `segment` evaluates one segment from four keys, such as `catmull_rom` or `squad`.
+/// The first and last keys are repeated to complete the segments at the ends,
+/// and `t` is clamped to the range of the keys.
+///
+/// Panics if `keys` is empty.
+pub fn path<T, F>(keys: &[DualQuaternion<T>], t: T, segment: F) -> DualQuaternion<T>
+    where T: Float, F: Fn([DualQuaternion<T>; 4], T) -> DualQuaternion<T>
+{
+    assert!(!keys.is_empty());
+    let last = keys.len() - 1;
+    let t = t.max(T::zero()).min(T::from_f64(last as f64));
+    // `t` is not negative, so this is the floor
+    let u = t % T::one();
+    let start = t - u;
+    // `Float` has no conversion to integers, so the index of `start` is found by bisection
+    let (mut i, mut j) = (0, last);
+    while i < j {
+        let mid = j - (j - i) / 2;
+        if T::from_f64(mid as f64) <= start {
+            i = mid;
+        } else {
+            j = mid - 1;
+        }
+    }
+    if i == last {
+        return keys[last];
+    }
+    let key = |j: isize| keys[j.max(0).min(last as isize) as usize];
+    let i = i as isize;
+    segment([key(i - 1), key(i), key(i + 1), key(i + 2)], u)
+}
+
+/// Tests
+#[cfg(test)]
+mod test {
+
+    use quaternion;
+    use vecmath;
+
+    use test_util::assert_close;
+    use super::super::{from_rotation_and_translation, get_translation, pow, scale, sclerp, DualQuaternion};
+
+    const EPSILON: f64 = 0.000000001;
+
+    fn poses() -> [DualQuaternion<f64>; 5] {
+        let pose = |axis: [f64; 3], angle, t| {
+            from_rotation_and_translation(quaternion::axis_angle(vecmath::vec3_normalized(axis), angle), t)
+        };
+        [
+            pose([0.0, 0.0, 1.0], 0.0, [0.0, 0.0, 0.0]),
+            pose([0.0, 1.0, 1.0], 0.8, [1.0, 0.5, 0.0]),
+            // the same pose with the opposite sign tests the hemisphere alignment
+            scale(pose([1.0, 0.0, 1.0], 1.5, [2.0, -0.5, 1.0]), -1.0),
+            pose([1.0, 1.0, 0.0], 2.0, [3.0, 0.0, 2.0]),
+            pose([0.0, 0.0, 1.0], 2.5, [4.0, 1.0, 2.0]),
+        ]
+    }
+
+    #[test]
+    fn test_interpolates_keys() {
+        let q = poses();
+        let keys = [q[0], q[1], q[2], q[3]];
+        for segment in &[super::catmull_rom, super::squad] {
+            assert_close(segment(keys, 0.0), q[1], EPSILON);
+            assert_close(segment(keys, 1.0), q[2], EPSILON);
+        }
+        assert_close(super::kochanek_bartels(keys, 1.0, 0.5, -0.3, 0.2), q[2], EPSILON);
+        assert_close(super::bezier(keys, 0.0), q[0], EPSILON);
+        assert_close(super::bezier(keys, 1.0), q[3], EPSILON);
+        assert_close(super::path(&q, 3.0, super::catmull_rom), q[3], EPSILON);
+        assert_close(super::path(&q, 9.0, super::squad), q[4], EPSILON);
+        // the segment between keys 2 and 3, and the first one with the first key repeated
+        assert_close(super::path(&q, 2.25, super::catmull_rom), super::catmull_rom([q[1], q[2], q[3], q[4]], 0.25), EPSILON);
+        assert_close(super::path(&q, 0.5, super::squad), super::squad([q[0], q[0], q[1], q[2]], 0.5), EPSILON);
+    }
+
+    #[test]
+    fn test_constant_screw_motion() {
+        let m = from_rotation_and_translation(quaternion::axis_angle([0.0, 0.6, 0.8], 0.7), [0.2, 1.0, -0.5]);
+        let keys = [pow(m, 0.0), pow(m, 1.0), pow(m, 2.0), pow(m, 3.0)];
+        for &t in &[0.0, 0.3, 0.5, 0.8] {
+            let expected = pow(m, 1.0 + t);
+            assert_close(super::catmull_rom(keys, t), expected, 1e-9);
+            assert_close(super::squad(keys, t), expected, 1e-9);
+            assert_close(super::bezier([keys[1], pow(m, 4.0 / 3.0), pow(m, 5.0 / 3.0), keys[2]], t), expected, 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_continuous_velocity() {
+        // finite differences on either side of a key, translation and rotation parts
+        let keys = poses();
+        let h = 1e-5;
+        let velocity_jump = |f: &dyn Fn(f64) -> DualQuaternion<f64>| {
+            let (before, at, after) = (f(2.0 - h), f(2.0), f(2.0 + h));
+            let mut jump: f64 = 0.0;
+            let (tb, ta, tf) = (get_translation(before), get_translation(at), get_translation(after));
+            for i in 0..3 {
+                jump = jump.max(((tf[i] - ta[i]) - (ta[i] - tb[i])).abs() / h);
+            }
+            let (rb, ra, rf) = (before.0, at.0, after.0);
+            let (rb, rf) = (if quaternion::dot(ra, rb) < 0.0 { quaternion::scale(rb, -1.0) } else { rb },
+                            if quaternion::dot(ra, rf) < 0.0 { quaternion::scale(rf, -1.0) } else { rf });
+            jump = jump.max(((rf.0 - ra.0) - (ra.0 - rb.0)).abs() / h);
+            for i in 0..3 {
+                jump = jump.max(((rf.1[i] - ra.1[i]) - (ra.1[i] - rb.1[i])).abs() / h);
+            }
+            jump
+        };
+        assert!(velocity_jump(&|t| super::path(&keys, t, super::catmull_rom)) < 1e-3);
+        assert!(velocity_jump(&|t| super::path(&keys, t, super::squad)) < 1e-3);
+        assert!(velocity_jump(&|t| super::path(&keys, t, |q, t| sclerp(q[1], q[2], t))) > 1e-1);
+    }
+
+}
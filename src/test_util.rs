@@ -0,0 +1,48 @@
+//! Helpers shared by the tests of several modules
+
+// some helpers are only used by modules behind features
+#![allow(dead_code)]
+
+use std::fmt::Debug;
+use std::vec::Vec;
+
+use quaternion;
+use vecmath::{self, Vector3};
+use vecmath::traits::Float;
+
+use super::{abs, dot, from_rotation_and_translation, scale, DualQuaternion};
+
+/// Returns a pseudo-random number in `[-1, 1)`, advancing a linear congruential generator
+pub fn pseudo_random<T: Float>(state: &mut u32) -> T {
+    *state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+    T::from_f64((*state as f64) / 2147483648.0 - 1.0)
+}
+
+/// Returns `n` pseudo-random unit dual-quaternions with rotations of up to 3 radians
+pub fn random_poses<T: Float>(n: usize, state: &mut u32) -> Vec<DualQuaternion<T>> {
+    let (two, three) = (T::from_f64(2.0), T::from_f64(3.0));
+    (0..n).map(|_| {
+        let axis = [pseudo_random(state), pseudo_random(state), pseudo_random::<T>(state) + two];
+        let r = quaternion::axis_angle(vecmath::vec3_normalized(axis), pseudo_random::<T>(state) * three);
+        from_rotation_and_translation(r, [pseudo_random(state), pseudo_random(state), pseudo_random(state)])
+    }).collect()
+}
+
+/// Returns `n` pseudo-random points in `[-1, 1)` cubed
+pub fn random_points<T: Float>(n: usize, state: &mut u32) -> Vec<Vector3<T>> {
+    (0..n).map(|_| [pseudo_random(state), pseudo_random(state), pseudo_random(state)]).collect()
+}
+
+/// Asserts that two unit dual-quaternions are the same transform, up to sign
+///
+/// The sign of `b` is aligned with `a`, then all 8 components are compared,
+/// so the rotation error is linear in the angle.
+pub fn assert_close<T: Float + Debug>(a: DualQuaternion<T>, b: DualQuaternion<T>, eps: T) {
+    let aligned = if dot(a, b) < T::zero() { scale(b, -T::one()) } else { b };
+    let components = |q: DualQuaternion<T>| {
+        [(q.0).0, (q.0).1[0], (q.0).1[1], (q.0).1[2], (q.1).0, (q.1).1[0], (q.1).1[1], (q.1).1[2]]
+    };
+    for (&x, &y) in components(a).iter().zip(&components(aligned)) {
+        assert!(abs(x - y) < eps, "{:?} != {:?}", a, b);
+    }
+}
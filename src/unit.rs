@@ -0,0 +1,235 @@
+//! Unit dual-quaternions, which always represent rigid transforms
+
+#[cfg(feature = "std")]
+use std::error::Error;
+use core::fmt;
+use core::ops::Mul;
+
+use quaternion::{self, Quaternion};
+use vecmath::Vector3;
+use vecmath::traits::Float;
+
+use math;
+use super::{dot, within, DualQuat, DualQuaternion};
+
+/// An error returned when a dual-quaternion doesn't represent a rigid transform
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnitError {
+    /// The real part doesn't have unit length
+    NotNormalized,
+    /// The dual part isn't orthogonal to the real part
+    NotOrthogonal,
+}
+
+impl fmt::Display for UnitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            UnitError::NotNormalized => "real part of dual-quaternion does not have unit length",
+            UnitError::NotOrthogonal => "dual part of dual-quaternion is not orthogonal to real part",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for UnitError {}
+
+/// A dual-quaternion with a unit real part and a dual part orthogonal to it
+///
+/// Every value of this type is a rigid transform, so operations that
+/// assume a unit dual-quaternion, such as `get_translation` and `inverse`, are exact up to rounding.
+/// Products and powers are renormalized, so long chains of compositions don't drift away from unit length.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UnitDualQuaternion<T>(DualQuaternion<T>);
+
+impl<T: Float> UnitDualQuaternion<T> {
+    /// Checks that a dual-quaternion is a unit dual-quaternion, within a tolerance of `1e-4`,
+    /// and normalizes it
+    ///
+    /// Components that are infinite or NaN fail the checks.
+    pub fn new(q: DualQuaternion<T>) -> Result<UnitDualQuaternion<T>, UnitError> {
+        let tolerance = T::from_f64(1e-4);
+        // NaN fails both checks
+        if !within(dot(q, q) - T::one(), tolerance) {
+            return Err(UnitError::NotNormalized);
+        }
+        if !within(quaternion::dot(q.0, q.1), tolerance) {
+            return Err(UnitError::NotOrthogonal);
+        }
+        Ok(UnitDualQuaternion(super::normalize(q)))
+    }
+
+    /// Normalizes a dual-quaternion, projecting it onto the nearest unit dual-quaternion
+    ///
+    /// The real part must not be zero.
+    #[inline(always)]
+    pub fn new_normalize(q: DualQuaternion<T>) -> UnitDualQuaternion<T> {
+        UnitDualQuaternion(super::normalize(q))
+    }
+
+    /// Wraps a dual-quaternion without checking that it is a unit dual-quaternion
+    #[inline(always)]
+    pub fn new_unchecked(q: DualQuaternion<T>) -> UnitDualQuaternion<T> {
+        UnitDualQuaternion(q)
+    }
+
+    /// Constructs identity unit dual-quaternion
+    #[inline(always)]
+    pub fn id() -> UnitDualQuaternion<T> {
+        UnitDualQuaternion(super::id())
+    }
+
+    /// Constructs a unit dual-quaternion from rotation and translation,
+    /// normalizing the rotation
+    #[inline(always)]
+    pub fn from_rotation_and_translation(rotation: Quaternion<T>, translation: Vector3<T>) -> UnitDualQuaternion<T> {
+        let rotation = quaternion::scale(rotation, T::one() / math::sqrt(quaternion::square_len(rotation)));
+        UnitDualQuaternion(super::from_rotation_and_translation(rotation, translation))
+    }
+
+    /// Wraps a product or power of unit dual-quaternions, renormalizing away the rounding errors
+    #[inline(always)]
+    fn from_product(mut q: DualQuaternion<T>) -> UnitDualQuaternion<T> {
+        super::renormalize_in_place(&mut q);
+        UnitDualQuaternion(q)
+    }
+
+    /// Returns the wrapped dual-quaternion
+    #[inline(always)]
+    pub fn into_inner(self) -> DualQuaternion<T> {
+        self.0
+    }
+
+    /// Returns the inverse, which is also the conjugate
+    #[inline(always)]
+    pub fn inverse(self) -> UnitDualQuaternion<T> {
+        UnitDualQuaternion(super::inverse(self.0))
+    }
+
+    /// Returns the relative transform from `self` to `other`, see `between`
+    #[inline(always)]
+    pub fn between(self, other: UnitDualQuaternion<T>) -> UnitDualQuaternion<T> {
+        UnitDualQuaternion::from_product(super::between(self.0, other.0))
+    }
+
+    /// Screw linear interpolation from `self` to `other`, see `sclerp`
+    #[inline(always)]
+    pub fn sclerp(self, other: UnitDualQuaternion<T>, t: T) -> UnitDualQuaternion<T> {
+        UnitDualQuaternion::from_product(super::sclerp(self.0, other.0, t))
+    }
+
+    /// Raises to a real power, see `pow`
+    #[inline(always)]
+    pub fn pow(self, t: T) -> UnitDualQuaternion<T> {
+        UnitDualQuaternion::from_product(super::pow(self.0, t))
+    }
+
+    /// Extracts the rotation component
+    #[inline(always)]
+    pub fn get_rotation(self) -> Quaternion<T> {
+        super::get_rotation(self.0)
+    }
+
+    /// Extracts the translation component
+    #[inline(always)]
+    pub fn get_translation(self) -> Vector3<T> {
+        super::get_translation(self.0)
+    }
+
+    /// Transforms a point, see `transform_point`
+    #[inline(always)]
+    pub fn transform_point(self, p: Vector3<T>) -> Vector3<T> {
+        super::transform_point(self.0, p)
+    }
+
+    /// Transforms a direction vector, see `transform_vector`
+    #[inline(always)]
+    pub fn transform_vector(self, v: Vector3<T>) -> Vector3<T> {
+        super::transform_vector(self.0, v)
+    }
+}
+
+impl<T: Float> Mul for UnitDualQuaternion<T> {
+    type Output = UnitDualQuaternion<T>;
+
+    #[inline(always)]
+    fn mul(self, other: UnitDualQuaternion<T>) -> UnitDualQuaternion<T> {
+        UnitDualQuaternion::from_product(super::mul(self.0, other.0))
+    }
+}
+
+impl<T> From<UnitDualQuaternion<T>> for DualQuat<T> {
+    #[inline(always)]
+    fn from(q: UnitDualQuaternion<T>) -> DualQuat<T> {
+        (q.0).into()
+    }
+}
+
+impl<T> AsRef<DualQuaternion<T>> for UnitDualQuaternion<T> {
+    #[inline(always)]
+    fn as_ref(&self) -> &DualQuaternion<T> {
+        &self.0
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod test {
+
+    use std::f64::consts::PI;
+    use quaternion;
+
+    use super::{UnitDualQuaternion, UnitError};
+    use super::super::{dot, from_rotation_and_translation, scale};
+
+    const EPSILON: f64 = 0.000000001;
+
+    #[test]
+    fn test_checked_construction() {
+        let q = from_rotation_and_translation(quaternion::axis_angle([0.0, 1.0, 0.0], 0.5), [1.0f64, 2.0, 3.0]);
+        assert!(UnitDualQuaternion::new(q).is_ok());
+        assert_eq!(UnitDualQuaternion::new(scale(q, 2.0)), Err(UnitError::NotNormalized));
+
+        let mut skewed = q;
+        (skewed.1).0 += 1.0;
+        assert_eq!(UnitDualQuaternion::new(skewed), Err(UnitError::NotOrthogonal));
+
+        // normalizing enforces both constraints
+        let u = UnitDualQuaternion::new_normalize(skewed).into_inner();
+        assert!((dot(u, u) - 1.0).abs() < EPSILON);
+        assert!(quaternion::dot(u.0, u.1).abs() < EPSILON);
+        assert!(UnitDualQuaternion::new(UnitDualQuaternion::new_normalize(scale(q, 3.0)).into_inner()).is_ok());
+
+        let nan = ((f64::NAN, [0.0; 3]), (0.0, [0.0; 3]));
+        assert_eq!(UnitDualQuaternion::new(nan), Err(UnitError::NotNormalized));
+        let mut nan = q;
+        (nan.1).1[1] = f64::NAN;
+        assert_eq!(UnitDualQuaternion::new(nan), Err(UnitError::NotOrthogonal));
+
+        // values within the tolerance are normalized
+        let u = UnitDualQuaternion::new(scale(q, 1.00004)).unwrap().into_inner();
+        assert!((dot(u, u) - 1.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_closed_operations() {
+        // an unnormalized rotation is normalized on construction
+        let a = UnitDualQuaternion::from_rotation_and_translation((1.0, [1.0, 0.0, 0.0]), [1.0, 0.0, 0.0]);
+        let b = UnitDualQuaternion::from_rotation_and_translation(
+            quaternion::axis_angle([0.0, 0.0, 1.0], PI / 3.0), [0.0, -2.0, 5.0]
+        );
+
+        let mut q = UnitDualQuaternion::id();
+        for _ in 0..100 {
+            q = q * a * b.inverse() * b.sclerp(a, 0.3) * a.between(b).pow(0.7);
+        }
+        let q = q.into_inner();
+        assert!((dot(q, q) - 1.0).abs() < EPSILON);
+        assert!(quaternion::dot(q.0, q.1).abs() < EPSILON);
+
+        let p = (a * a.inverse()).transform_point([1.0, 2.0, 3.0]);
+        assert!((p[0] - 1.0).abs() < EPSILON);
+        assert!((p[1] - 2.0).abs() < EPSILON);
+        assert!((p[2] - 3.0).abs() < EPSILON);
+    }
+
+}